@@ -1,18 +1,112 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::iter;
+
+use itertools::Itertools;
 use crate::{Solution, SolutionPair};
-use crate::etc::utils::DOUBLE_NEWLINE;
-use std::fs::read_to_string;
+use crate::etc::utils::stream_input;
 
 ///////////////////////////////////////////////////////////////////////////////
 
+const TOP_K: usize = 3;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Elf {
+    calories: u64,
+    // Reversed so that, in case of a tie, the elf that came first ranks higher
+    index: Reverse<usize>,
+}
+
 pub fn solve() -> SolutionPair {
-    let mut calories: Vec<u32> = read_to_string("input/day01.txt").unwrap()
-        .split(DOUBLE_NEWLINE)
-        .map(|elf| elf.lines().map(|x| x.parse::<u32>().unwrap()).sum())
-        .collect();
+    // The elves are read one by one, so the input never needs to be fully in memory
+    let top = top_k(elf_totals(stream_input(1)), TOP_K);
+
+    let sol1 = top[0].calories;
+    let sol2: u64 = top.iter().map(|elf| elf.calories).sum();
+
+    // Also tell which elves those are, counting from 1 in the order of the input
+    let number = |elf: &Elf| format!("#{}", elf.index.0 + 1);
+    let sol1 = Solution::from(sol1).with_note(format!("elf {}", number(&top[0])));
+    let sol2 = Solution::from(sol2).with_note(format!("elves {}", top.iter().map(number).join(", ")));
+
+    (sol1, sol2)
+}
+
+// Adds up the calories carried by every elf, each one being a group of lines
+// separated from the rest by blank lines
+fn elf_totals(mut lines: impl Iterator<Item = String>) -> impl Iterator<Item = u64> {
+    iter::from_fn(move || {
+        let mut total = None;
+
+        for line in lines.by_ref() {
+            match (line.is_empty(), total) {
+                (true, None) => continue,
+                (true, Some(_)) => break,
+                (false, _) => *total.get_or_insert(0) += line.parse::<u64>().unwrap(),
+            }
+        }
+
+        total
+    })
+}
+
+// Finds the K elves carrying the most calories, from most to least, keeping
+// only K elves in memory at a time. The smallest of them is always at the top
+// of the heap, ready to be replaced by a better one.
+fn top_k(totals: impl Iterator<Item = u64>, k: usize) -> Vec<Elf> {
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+
+    for (i, calories) in totals.enumerate() {
+        heap.push(Reverse(Elf { calories, index: Reverse(i) }));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+
+    heap.into_sorted_vec().into_iter().map(|Reverse(elf)| elf).collect()
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    fn lines(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split('\n').map(str::to_owned)
+    }
+
+    // The calories and position of every elf in the result
+    fn ranking(top: &[Elf]) -> Vec<(u64, usize)> {
+        top.iter().map(|elf| (elf.calories, elf.index.0)).collect()
+    }
+
+    #[test]
+    fn solves_the_example() {
+        let totals = elf_totals(lines(EXAMPLE)).collect_vec();
+        assert_eq!(totals, [6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(ranking(&top_k(totals.into_iter(), 3)), [(24000, 3), (11000, 2), (10000, 4)]);
+    }
+
+    #[test]
+    fn asking_for_more_elves_than_there_are() {
+        let top = top_k(elf_totals(lines(EXAMPLE)), 10);
+        assert_eq!(ranking(&top), [(24000, 3), (11000, 2), (10000, 4), (6000, 0), (4000, 1)]);
+        assert!(top_k(iter::empty(), 3).is_empty());
+    }
 
-    calories.sort_by(|a, b| b.cmp(a));
-    let sol1 = calories[0];
-    let sol2: u32 = calories[0..3].iter().sum();
+    #[test]
+    fn ties_go_to_the_earlier_elf() {
+        assert_eq!(ranking(&top_k([5, 7, 7, 3].into_iter(), 2)), [(7, 1), (7, 2)]);
+        assert_eq!(ranking(&top_k([7, 5, 7, 7].into_iter(), 2)), [(7, 0), (7, 2)]);
+        assert_eq!(ranking(&top_k([4, 4, 4].into_iter(), 1)), [(4, 0)]);
+    }
 
-    (Solution::from(sol1), Solution::from(sol2))
-}
\ No newline at end of file
+    #[test]
+    fn several_blank_lines_separate_only_two_elves() {
+        let totals = elf_totals(lines("\n\n1\n2\n\n\n\n3\n\n")).collect_vec();
+        assert_eq!(totals, [3, 3]);
+    }
+}
@@ -1,41 +1,163 @@
 use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
+use crate::etc::read_input;
+use Outcome::*;
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/** A game like Rock-Paper-Scissors, with an odd amount of shapes arranged in
+    a cycle, where every shape beats the half of the others that come right
+    before it. For example, Rock-Spock-Paper-Lizard-Scissors also works. */
+struct CyclicGame {
+    // Points for choosing each shape, in the order of the cycle
+    shape_scores: Vec<usize>,
+    // Points for losing, drawing and winning
+    outcome_scores: [usize; 3],
+    // The letters that represent each shape in each column of the guide
+    opponent_letters: Vec<char>,
+    own_letters: Vec<char>,
+    // The letters for a loss, a draw and a win, when the second column is an outcome
+    outcome_letters: Vec<char>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Outcome {
+    Loss, Draw, Win
+}
+
+// The two ways in which the second column of the guide can be read
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Strategy {
+    // The shape that we have to play
+    Shape,
+    // How the round has to end
+    Outcome,
+}
+
 pub fn solve() -> SolutionPair {
-    let (sol1, sol2) = read_to_string("input/day02.txt").unwrap()
-        .lines()
-        .map(solve_line)
-        .fold((0, 0), |(x1, y1), (x2, y2)| (x1 + x2, y1 + y2));
+    let input = read_input(2);
+    let game = CyclicGame::new(vec![1, 2, 3], [0, 3, 6], "ABC", "XYZ", "XYZ");
+
+    let sol1 = game.total_score(&input, Strategy::Shape).unwrap_or_else(|e| panic!("{e}"));
+    let sol2 = game.total_score(&input, Strategy::Outcome).unwrap_or_else(|e| panic!("{e}"));
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn solve_line(line: &str) -> (usize, usize) {
-    let mut chars = line.chars();
-    let opp = chars.next().unwrap() as usize - 'A' as usize;
-    let us = chars.nth(1).unwrap() as usize - 'X' as usize;
-    
-    let sol1 = (us + 1) + SCORES_TABLE[opp][us];
-    let sol2 = VALUES_TABLE[opp][us] + (us * 3);
-    (sol1, sol2)
+///////////////////////////////////////////////////////////////////////////////
+
+impl CyclicGame {
+    pub fn new(shape_scores: Vec<usize>, outcome_scores: [usize; 3], opponent_letters: &str, own_letters: &str, outcome_letters: &str) -> Self {
+        let n_shapes = shape_scores.len();
+        assert!(n_shapes % 2 == 1, "The game needs an odd number of shapes to be fair, found {n_shapes}");
+
+        let (opponent_letters, own_letters): (Vec<char>, Vec<char>) = (opponent_letters.chars().collect(), own_letters.chars().collect());
+        assert!(opponent_letters.len() == n_shapes && own_letters.len() == n_shapes,
+                "Every column needs one letter per shape: {n_shapes} shapes but letters {opponent_letters:?} and {own_letters:?}");
+
+        let outcome_letters: Vec<char> = outcome_letters.chars().collect();
+        assert!(outcome_letters.len() == 3, "There must be a letter for each outcome, found {outcome_letters:?}");
+
+        Self { shape_scores, outcome_scores, opponent_letters, own_letters, outcome_letters }
+    }
+
+    // Adds up the score of every round in the guide, read according to the strategy
+    pub fn total_score(&self, guide: &str, strategy: Strategy) -> Result<usize, String> {
+        guide.lines()
+            .map(|line| self.parse_round(line, strategy).map(|(opp, col)| self.score(opp, col, strategy)))
+            .sum()
+    }
+
+    // Reads a line of the guide, checking that both letters are valid. The second
+    // column is returned as the index of its letter among the shapes or outcomes.
+    pub fn parse_round(&self, line: &str, strategy: Strategy) -> Result<(usize, usize), String> {
+        let (opp, own) = line.split_once(' ').ok_or_else(|| format!("Malformed line in the guide: {line:?}"))?;
+
+        let find = |letter: &str, letters: &[char]| {
+            let mut chars = letter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => letters.iter().position(|&l| l == ch),
+                _ => None,
+            }.ok_or_else(|| format!("Unknown letter {letter:?} in {line:?}, expected one of {letters:?}"))
+        };
+
+        let own_letters = match strategy {
+            Strategy::Shape => &self.own_letters,
+            Strategy::Outcome => &self.outcome_letters,
+        };
+
+        Ok((find(opp, &self.opponent_letters)?, find(own, own_letters)?))
+    }
+
+    // Score of a round given the opponent's shape and the second column
+    // of the guide, as returned by parse_round with the same strategy
+    pub fn score(&self, opponent: usize, column: usize, strategy: Strategy) -> usize {
+        let (own, outcome) = match strategy {
+            Strategy::Shape => (column, self.outcome(column, opponent)),
+            Strategy::Outcome => {
+                let outcome = [Loss, Draw, Win][column];
+                (self.shape_for(opponent, outcome), outcome)
+            },
+        };
+
+        self.shape_scores[own] + self.outcome_scores[outcome as usize]
+    }
+
+    // Every shape beats the (n - 1) / 2 shapes before it, and loses against the rest
+    fn outcome(&self, own: usize, opponent: usize) -> Outcome {
+        let n = self.shape_scores.len();
+        match (own + n - opponent) % n {
+            0 => Draw,
+            diff if diff <= n / 2 => Win,
+            _ => Loss,
+        }
+    }
+
+    fn shape_for(&self, opponent: usize, outcome: Outcome) -> usize {
+        let n = self.shape_scores.len();
+        match outcome {
+            Draw => opponent,
+            Win => (opponent + 1) % n,
+            Loss => (opponent + n - 1) % n,
+        }
+    }
 }
 
-// Scores for a given combination of Rock/Paper/Scissors
-static SCORES_TABLE: [[usize; 3]; 3] = [ 
-//     Us
-//   R  P  S
-    [3, 6, 0], // R
-    [0, 3, 6], // P  Opponent
-    [6, 0 ,3], // S
-];
-
-// Value of the shape that achieves a given result against the opponent's shape
-static VALUES_TABLE: [[usize; 3]; 3] = [ 
-//   Result
-//   L  D  W
-    [3, 1, 2], // R
-    [1, 2, 3], // P  Opponent
-    [2, 3 ,1], // S
-];
\ No newline at end of file
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rock, Spock, Paper, Lizard, Scissors
+    fn rpsls() -> CyclicGame {
+        CyclicGame::new(vec![1, 2, 3, 4, 5], [0, 3, 6], "ABCDE", "VWXYZ", "XYZ")
+    }
+
+    #[test]
+    fn plays_the_example() {
+        let game = CyclicGame::new(vec![1, 2, 3], [0, 3, 6], "ABC", "XYZ", "XYZ");
+        let guide = "A Y\nB X\nC Z";
+        assert_eq!(game.total_score(guide, Strategy::Shape), Ok(15));
+        assert_eq!(game.total_score(guide, Strategy::Outcome), Ok(12));
+    }
+
+    #[test]
+    fn plays_rock_spock_paper_lizard_scissors() {
+        let game = rpsls();
+        // Spock beats rock, but loses against paper
+        assert_eq!(game.total_score("A W", Strategy::Shape), Ok(2 + 6));
+        assert_eq!(game.total_score("C W", Strategy::Shape), Ok(2));
+        // Spock beats the shapes right before it in the cycle, so playing rock loses
+        assert_eq!(game.total_score("B X", Strategy::Outcome), Ok(1));
+        assert_eq!(game.total_score("E Z", Strategy::Outcome), Ok(1 + 6));
+    }
+
+    #[test]
+    fn rejects_letters_that_are_not_outcomes() {
+        let game = rpsls();
+        assert_eq!(game.total_score("A V", Strategy::Shape), Ok(1 + 3));
+        assert!(game.total_score("A V", Strategy::Outcome).is_err());
+        assert!(game.total_score("A Y\nB W", Strategy::Outcome).is_err());
+        assert!(game.total_score("F X", Strategy::Outcome).is_err());
+    }
+}
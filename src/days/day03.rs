@@ -1,36 +1,90 @@
 use crate::{Solution, SolutionPair};
 use itertools::Itertools;
-use std::fs::read_to_string;
+use crate::etc::read_input;
+use crate::etc::bitset::BitSet;
 
 ///////////////////////////////////////////////////////////////////////////////
 
+// Every item type, in increasing order of priority starting from 1
+const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const GROUP_SIZE: usize = 3;
+
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day03.txt").unwrap();
+    let input = read_input(3);
+    let alphabet = ALPHABET.chars().collect_vec();
+
+    let sol1: usize = input.lines()
+        .map(|line| find_repeated(compartments(line), &alphabet).unwrap_or_else(|e| panic!("{e}")))
+        .sum();
 
-    let sol1: u32 = input.lines().map(|line| {
-        let half = line.len() / 2;
-        find_repeated([&line[..half], &line[half..]])
-    }).sum();
-    
-    let sol2: u32 = input.lines().chunks(3).into_iter().map(find_repeated).sum();
+    let sol2: usize = input.lines().chunks(GROUP_SIZE).into_iter()
+        .map(|group| find_repeated(group, &alphabet).unwrap_or_else(|e| panic!("{e}")))
+        .sum();
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn find_repeated<'a, I>(str_iter: I) -> u32 
+// Splits a rucksack into its two halves, which have the same amount of items.
+// Items are characters, which are not always a single byte long.
+fn compartments(line: &str) -> [&str; 2] {
+    let middle = line.chars().count() / 2;
+    let split = line.char_indices().nth(middle).map_or(line.len(), |(i, _)| i);
+    let (first, second) = line.split_at(split);
+    [first, second]
+}
+
+// Finds the priority of the only item type that all the lists have in common
+fn find_repeated<'a, I>(lists: I, alphabet: &[char]) -> Result<usize, String>
 where I: IntoIterator<Item = &'a str> {
-    // Turns each string into an u64 where a set bit indicates that it contains the item,
-    // and then ANDs them together to find the only common item
-    str_iter.into_iter()
-        .map(|string| string.chars().map(|ch| 1 << char2prio(ch)).fold(0, |a, b| a | b))
-        .fold(u64::MAX, |a, b| a & b)
-        .trailing_zeros()
+    let lists = lists.into_iter().collect_vec();
+    let common = common_items(&lists, alphabet)?;
+
+    match common.iter().collect_vec().as_slice() {
+        [prio] => Ok(*prio),
+        [] => Err(format!("No common items in {lists:?}")),
+        many => {
+            let items = many.iter().map(|&prio| alphabet[prio - 1]).collect::<String>();
+            Err(format!("More than one common item ({items}) in {lists:?}"))
+        },
+    }
+}
+
+// Turns each list into a set of priorities, and then intersects all of
+// them to find out the priorities of the items they all have in common
+fn common_items(lists: &[&str], alphabet: &[char]) -> Result<BitSet, String> {
+    let mut sets = lists.iter().map(|list| {
+        list.chars().map(|ch| item_priority(ch, alphabet)).collect::<Result<BitSet, _>>()
+    });
+
+    let first = sets.next().ok_or("Can't find common items in an empty group")??;
+    sets.try_fold(first, |common, set| Ok(common.intersection(&set?)))
 }
 
-fn char2prio(ch: char) -> u32 {
-    match ch {
-        'a'..='z' => ch as u32 - 'a' as u32 + 1,
-        'A'..='Z' => ch as u32 - 'A' as u32 + 27,
-        _ => unreachable!()
+fn item_priority(ch: char, alphabet: &[char]) -> Result<usize, String> {
+    alphabet.iter().position(|&item| item == ch)
+        .map(|i| i + 1)
+        .ok_or_else(|| format!("Unknown item type: {ch:?}"))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_by_characters() {
+        assert_eq!(compartments("abcd"), ["ab", "cd"]);
+        assert_eq!(compartments("ééab"), ["éé", "ab"]);
+        assert_eq!(compartments(""), ["", ""]);
+    }
+
+    #[test]
+    fn finds_items_in_any_alphabet() {
+        let alphabet = "αβγδ".chars().collect_vec();
+        assert_eq!(find_repeated(compartments("αγβγ"), &alphabet), Ok(3));
+        assert_eq!(find_repeated(["αβ", "βγ", "δβ"], &alphabet), Ok(2));
+        assert!(find_repeated(compartments("αβγδ"), &alphabet).is_err());
+        assert!(find_repeated(compartments("aa"), &alphabet).is_err());
     }
-}
\ No newline at end of file
+}
@@ -1,44 +1,88 @@
 use itertools::Itertools;
 use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
+use crate::etc::read_input;
+use crate::etc::intervals::{self, IntervalSet};
 
 ///////////////////////////////////////////////////////////////////////////////
 
-struct RangePair {
-    start_a: i32,
-    end_a: i32,
-    start_b: i32,
-    end_b: i32,
+type Sections = IntervalSet<i32>;
+
+// The section assignments of a group of elves, one per elf
+struct Group {
+    assignments: Vec<Sections>,
 }
 
 pub fn solve() -> SolutionPair {
-    let pairs = read_to_string("input/day04.txt").unwrap()
+    let groups = read_input(4)
         .lines()
-        .map(RangePair::from_line)
+        .map(Group::from_line)
         .collect_vec();
 
-    let sol1 = pairs.iter().filter(|p| p.has_full_overlap()).count();
-    let sol2 = pairs.iter().filter(|p| p.has_partial_overlap()).count();
+    let sol1 = groups.iter().filter(|g| g.has_full_overlap()).count();
+    let sol2 = groups.iter().filter(|g| g.overlap_len() > 0).count();
+
+    // Also find out which sections are assigned to the most elves, across every group
+    let (elves, sections) = intervals::max_coverage(groups.iter().flat_map(|g| &g.assignments));
+    let sol2 = Solution::from(sol2).with_note(format!("sections {sections} are assigned to {elves} elves"));
 
-    (Solution::from(sol1), Solution::from(sol2))
+    (Solution::from(sol1), sol2)
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
-impl RangePair {
+impl Group {
+    // A line lists the assignments separated by commas, as ranges like 2-4
     pub fn from_line(line: &str) -> Self {
-        let (left, right) = line.split(',').next_tuple().unwrap();
-        let (start_a, end_a) = left.split('-').map(|x| x.parse().unwrap()).next_tuple().unwrap();
-        let (start_b, end_b) = right.split('-').map(|x| x.parse().unwrap()).next_tuple().unwrap();
-        Self { start_a, end_a, start_b, end_b }
+        let assignments = line.split(',').map(|range| {
+            let (start, end) = range.split('-').map(|x| x.parse().unwrap()).next_tuple()
+                .unwrap_or_else(|| panic!("Not a valid range: {range:?}"));
+            Sections::single(start, end)
+        }).collect();
+
+        Self { assignments }
+    }
+
+    // The sections assigned to every elf in the group
+    pub fn overlap(&self) -> Sections {
+        self.assignments.iter().skip(1)
+            .fold(self.assignments[0].clone(), |common, a| common.intersection(a))
+    }
+
+    pub fn overlap_len(&self) -> i32 {
+        self.overlap().len()
     }
 
+    // Whether one of the assignments contains all the others
     pub fn has_full_overlap(&self) -> bool {
-        (self.start_a <= self.start_b && self.end_a >= self.end_b) ||
-        (self.start_b <= self.start_a && self.end_b >= self.end_a)
+        let all = self.assignments.iter().fold(Sections::new(), |all, a| all.union(a));
+        self.assignments.iter().any(|a| a.is_superset(&all))
     }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+    #[test]
+    fn solves_the_example() {
+        let groups = EXAMPLE.lines().map(Group::from_line).collect_vec();
+        assert_eq!(groups.iter().filter(|g| g.has_full_overlap()).count(), 2);
+        assert_eq!(groups.iter().filter(|g| g.overlap_len() > 0).count(), 4);
+    }
+
+    #[test]
+    fn finds_the_most_contested_sections() {
+        let groups = EXAMPLE.lines().map(Group::from_line).collect_vec();
+        let (elves, sections) = intervals::max_coverage(groups.iter().flat_map(|g| &g.assignments));
+        assert_eq!((elves, sections.to_string()), (8, "6".to_owned()));
 
-    pub fn has_partial_overlap(&self) -> bool {
-        self.start_a <= self.end_b && self.start_b <= self.end_a
+        // Every section with the highest count is reported, even if they're not contiguous
+        let sets = [Sections::single(1, 3), Sections::single(2, 5), Sections::single(5, 8)];
+        let (elves, sections) = intervals::max_coverage(&sets);
+        assert_eq!((elves, sections.to_string()), (2, "2-3, 5".to_owned()));
     }
 }
@@ -1,55 +1,417 @@
-use scanf::sscanf;
+// Solving the puzzle only takes the two CrateMovers going forwards, the bounded
+// mover and going back through the history are checked by the tests below
+#![allow(dead_code)]
+
 use itertools::Itertools;
 use crate::{Solution, SolutionPair};
-use crate::etc::utils::DOUBLE_NEWLINE;
-use std::fs::read_to_string;
+use crate::etc::read_input;
+use crate::etc::parsing::extract_ints;
+use crate::etc::visualize::{self, Visualize};
 
 ///////////////////////////////////////////////////////////////////////////////
 
 type Instruction = (usize, usize, usize);
+type Crate = String;
+type Stacks = Vec<Vec<Crate>>;
+
+/** The stacks of crates as drawn in the puzzle input, along with the label
+    of every stack. Crates can have labels of any length, like [AB], and
+    stacks can be numbered with any amount of digits. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Drawing {
+    labels: Vec<String>,
+    stacks: Stacks,
+}
+
+// The way in which a crane lifts crates from one stack to another
+trait Mover {
+    // Moves the given amount of crates from the top of a stack onto another
+    fn move_crates(&self, from: &mut Vec<Crate>, to: &mut Vec<Crate>, amount: usize);
+
+    // Puts back the crates moved by move_crates with the same arguments. Moving
+    // them back the same way works for cranes that always handle them alike.
+    fn undo_move(&self, from: &mut Vec<Crate>, to: &mut Vec<Crate>, amount: usize) {
+        self.move_crates(to, from, amount);
+    }
+}
+
+// Moves the crates one by one, so they end up in reverse order
+struct CrateMover9000;
+
+// Moves all the crates at once, so they keep their order
+struct CrateMover9001;
+
+// Moves the crates in batches of up to a certain size, each batch keeping its order
+struct BoundedMover {
+    capacity: usize,
+}
+
+/** A crane working on the stacks of crates, which remembers every step it
+    has done, so that they can be undone to go back to any previous state */
+struct Crane<M: Mover> {
+    mover: M,
+    drawing: Drawing,
+    history: Vec<Instruction>,
+}
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day05.txt").unwrap();
-    let (header_text, body_text) = input.split_once(DOUBLE_NEWLINE).unwrap();
-    let header = header_text.lines().collect_vec();
+    let input = read_input(5);
+    let (header_text, body_text) = input.paragraphs().collect_tuple().unwrap();
 
-    let n_stacks = (header.last().unwrap().len() + 1) / 4;
-    let stacks = (0..n_stacks).map(|i| read_stack(i, &header)).collect_vec();
-    let instrs = body_text.lines().map(line_to_instr).collect_vec();
+    let drawing = Drawing::parse(header_text).unwrap_or_else(|e| panic!("{e}"));
+    let instrs = body_text.lines().map(|line| line_to_instr(line, &drawing)).collect_vec();
+
+    let sol1 = process_stacks(CrateMover9000, &drawing, &instrs);
+    let sol2 = process_stacks(CrateMover9001, &drawing, &instrs);
 
-    let sol1 = process_stacks(&stacks, &instrs, false);
-    let sol2 = process_stacks(&stacks, &instrs, true);
-    
     (Solution::from(sol1), Solution::from(sol2))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 // Runs all the instructions on the given stacks, returning the final string
-fn process_stacks(stacks: &[Vec<char>], instructions: &[Instruction], new_model: bool) -> String {
-    let mut stacks = stacks.to_owned();
+fn process_stacks<M: Mover>(mover: M, drawing: &Drawing, instructions: &[Instruction]) -> String {
+    let mut crane = Crane::new(mover, drawing.clone());
+    visualize::new_simulation();
+
+    for &instr in instructions {
+        crane.apply(instr).unwrap_or_else(|e| panic!("{e}"));
+        visualize::show(&crane);
+    }
+
+    crane.tops()
+}
+
+// Lets a mover work on two of the stacks. Moving crates onto the stack they come
+// from leaves it as it was, so the mover is only used for different stacks.
+fn transfer<F>(stacks: &mut Stacks, from: usize, to: usize, mover: F)
+where F: FnOnce(&mut Vec<Crate>, &mut Vec<Crate>) {
+    if from != to {
+        // Take the origin stack out to be able to borrow both at once
+        let mut origin = std::mem::take(&mut stacks[from]);
+        mover(&mut origin, &mut stacks[to]);
+        stacks[from] = origin;
+    }
+}
+
+// Reads an instruction, translating the stack labels into indices
+fn line_to_instr(line: &str, drawing: &Drawing) -> Instruction {
+    let (amount, from, to): (usize, usize, usize) = extract_ints(line).unwrap();
+    let index = |label: usize| drawing.stack_index(&label.to_string())
+        .unwrap_or_else(|| panic!("Unknown stack {label} in {line:?}"));
+    (amount, index(from), index(to))
+}
+
+// Finds every run of non-whitespace characters in a line, along with
+// the column (in characters, not bytes) where it starts
+fn tokens(line: &str) -> Vec<(usize, String)> {
+    let chars = line.chars().collect_vec();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        // Crates can't contain spaces, but their labels could contain brackets
+        let is_crate = chars[i] == '[';
+        while i < chars.len() && !chars[i].is_whitespace() && !(is_crate && i > start && chars[i - 1] == ']') {
+            i += 1;
+        }
+        tokens.push((start, chars[start..i].iter().collect()));
+    }
+
+    tokens
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl Mover for CrateMover9000 {
+    fn move_crates(&self, from: &mut Vec<Crate>, to: &mut Vec<Crate>, amount: usize) {
+        let i = from.len() - amount;
+        to.extend(from.drain(i..).rev());
+    }
+}
+
+impl Mover for CrateMover9001 {
+    fn move_crates(&self, from: &mut Vec<Crate>, to: &mut Vec<Crate>, amount: usize) {
+        let i = from.len() - amount;
+        to.extend(from.drain(i..));
+    }
+}
+
+impl Mover for BoundedMover {
+    fn move_crates(&self, from: &mut Vec<Crate>, to: &mut Vec<Crate>, amount: usize) {
+        let mut remaining = amount;
+        while remaining > 0 {
+            let batch = remaining.min(self.capacity.max(1));
+            CrateMover9001.move_crates(from, to, batch);
+            remaining -= batch;
+        }
+    }
+
+    // The last batch, which is the one on top, may be smaller than the rest
+    fn undo_move(&self, from: &mut Vec<Crate>, to: &mut Vec<Crate>, amount: usize) {
+        let capacity = self.capacity.max(1);
+        CrateMover9001.move_crates(to, from, amount % capacity);
+        for _ in 0..amount / capacity {
+            CrateMover9001.move_crates(to, from, capacity);
+        }
+    }
+}
+
+impl Drawing {
+    // Reads a drawing like the one in the puzzle input. The bottom row has the
+    // labels of the stacks, and every crate belongs to the stack whose label
+    // is right under it. Lines don't need to be padded with spaces.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let lines = text.lines().collect_vec();
+        let (label_row, crate_rows) = lines.split_last().ok_or("The drawing is empty")?;
+
+        let labels = tokens(label_row);
+        // The column of the middle character of every label
+        let centers = labels.iter().map(|(start, label)| start + (label.chars().count() - 1) / 2).collect_vec();
+        let mut stacks: Stacks = vec![vec![]; labels.len()];
+
+        for (level, row) in crate_rows.iter().rev().enumerate() {
+            for (start, token) in tokens(row) {
+                let len = token.chars().count();
+                if len < 3 || !token.starts_with('[') || !token.ends_with(']') {
+                    return Err(format!("Not a crate: {token:?} in {row:?}"));
+                }
+
+                let stack = centers.iter().position(|&c| start <= c && c < start + len)
+                    .ok_or_else(|| format!("The crate {token} at column {} isn't above any label", start + 1))?;
+                if stacks[stack].len() != level {
+                    return Err(format!("The crate {token} in stack {} is floating in the air", labels[stack].1));
+                }
+
+                stacks[stack].push(token[1..token.len() - 1].to_owned());
+            }
+        }
+
+        let labels = labels.into_iter().map(|(_, label)| label).collect();
+        Ok(Self { labels, stacks })
+    }
+
+    pub fn stack_index(&self, label: &str) -> Option<usize> {
+        self.labels.iter().position(|l| l == label)
+    }
 
-    for &(amount, from, to) in instructions {
-        let i = stacks[from].len() - amount;
-        let mut tmp = stacks[from].split_off(i);
-        if !new_model { tmp.reverse(); }
-        stacks[to].extend(tmp); 
+    // Draws the stacks back in the input format, with every column as wide
+    // as the widest crate or label, and the contents centered in it. For
+    // drawings with single-character crates and labels, this is the same
+    // text that was parsed, except for any trailing whitespace.
+    pub fn render(&self) -> String {
+        let crate_width = self.stacks.iter().flatten().map(|c| c.chars().count() + 2).max().unwrap_or(0);
+        let width = self.labels.iter().map(|l| l.chars().count()).max().unwrap_or(0).max(crate_width);
+        let center = |text: &str| {
+            let left = (width - text.chars().count()) / 2;
+            let right = width - left;
+            format!("{:left$}{text:right$}", "")
+        };
+
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let rows = (0..height).rev().map(|level| {
+            self.stacks.iter()
+                .map(|stack| center(&stack.get(level).map_or(String::new(), |c| format!("[{c}]"))))
+                .join(" ")
+        });
+        let label_row = self.labels.iter().map(|l| center(l)).join(" ");
+
+        rows.chain([label_row]).map(|row| row.trim_end().to_owned()).join("\n")
+    }
+}
+
+impl<M: Mover> Crane<M> {
+    pub fn new(mover: M, drawing: Drawing) -> Self {
+        Self { mover, drawing, history: vec![] }
+    }
+
+    // Performs a step, as long as the stacks exist and have enough crates
+    pub fn apply(&mut self, instr: Instruction) -> Result<(), String> {
+        let (amount, from, to) = instr;
+        let n_stacks = self.drawing.stacks.len();
+        if from >= n_stacks || to >= n_stacks {
+            return Err(format!("Invalid step: there are only {n_stacks} stacks, but a step uses stacks #{} and #{}", from + 1, to + 1));
+        }
+
+        let show = || format!("move {amount} from {} to {}", self.drawing.labels[from], self.drawing.labels[to]);
+        if amount > self.drawing.stacks[from].len() {
+            return Err(format!("Invalid step \"{}\": the stack only has {} crates", show(), self.drawing.stacks[from].len()));
+        }
+
+        self.history.push(instr);
+        transfer(&mut self.drawing.stacks, from, to, |origin, target| self.mover.move_crates(origin, target, amount));
+        Ok(())
+    }
+
+    // The crates at the top of every stack
+    pub fn tops(&self) -> String {
+        self.drawing.stacks.iter().filter_map(|st| st.last()).join("")
+    }
+}
+
+impl<M: Mover> Crane<M> {
+    pub fn steps_done(&self) -> usize {
+        self.history.len()
+    }
+
+    // Reverts the last step, returning its instruction
+    pub fn undo(&mut self) -> Option<Instruction> {
+        let (amount, from, to) = self.history.pop()?;
+        transfer(&mut self.drawing.stacks, from, to, |origin, target| self.mover.undo_move(origin, target, amount));
+        Some((amount, from, to))
     }
 
-    stacks.iter().map(|st| st.last().unwrap()).collect()
+    // The stacks as they were after the given amount of steps, found by
+    // undoing the steps that came after it on a copy of the stacks
+    pub fn state_at(&self, step: usize) -> Option<Stacks> {
+        let mut stacks = self.drawing.stacks.clone();
+        for &(amount, from, to) in self.history.get(step..)?.iter().rev() {
+            transfer(&mut stacks, from, to, |origin, target| self.mover.undo_move(origin, target, amount));
+        }
+        Some(stacks)
+    }
+
+    pub fn render_step(&self, step: usize) -> Option<String> {
+        self.state_at(step).map(|stacks| Drawing { labels: self.drawing.labels.clone(), stacks }.render())
+    }
 }
 
-// Creates the nth stack by reading its contents from the header in the input
-fn read_stack(n: usize, header: &[&str]) -> Vec<char> {
-    header.iter()
-          .rev()
-          .skip(1)  // Skip the stack numbers
-          .map(|line| line.chars().nth(4*n + 1).unwrap())
-          .take_while(|ch| *ch != ' ')
-          .collect()
+impl<M: Mover> Visualize for Crane<M> {
+    fn frame(&self) -> String {
+        self.drawing.render()
+    }
+
+    fn caption(&self) -> String {
+        match self.history.last() {
+            Some((amount, from, to)) => format!("Step {}: move {amount} from {} to {}", self.steps_done(), self.drawing.labels[*from], self.drawing.labels[*to]),
+            None => "Initial state".to_owned(),
+        }
+    }
 }
 
-pub fn line_to_instr(line: &str) -> Instruction {
-    let (mut amount, mut from, mut to) = (0, 0, 0);
-    sscanf!(line, "move {} from {} to {}", amount, from, to).expect("Error reading instruction");
-    (amount, from - 1, to - 1)
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+    const STEPS: &str = "move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2";
+
+    fn run<M: Mover>(mover: M) -> (Crane<M>, Vec<Stacks>) {
+        let drawing = Drawing::parse(EXAMPLE).unwrap();
+        let mut crane = Crane::new(mover, drawing.clone());
+        let mut states = vec![crane.drawing.stacks.clone()];
+
+        for line in STEPS.lines() {
+            crane.apply(line_to_instr(line, &drawing)).unwrap();
+            states.push(crane.drawing.stacks.clone());
+        }
+
+        (crane, states)
+    }
+
+    fn stack(crates: &str) -> Vec<Crate> {
+        crates.chars().map(String::from).collect()
+    }
+
+    #[test]
+    fn solves_the_example() {
+        assert_eq!(run(CrateMover9000).0.tops(), "CMZ");
+        assert_eq!(run(CrateMover9001).0.tops(), "MCD");
+    }
+
+    #[test]
+    fn undoing_goes_back_through_every_state() {
+        let (mut crane, states) = run(CrateMover9000);
+        for (step, state) in states.iter().enumerate() {
+            assert_eq!(crane.state_at(step).as_ref(), Some(state));
+        }
+        assert_eq!(crane.state_at(states.len()), None);
+        assert_eq!(crane.render_step(0).unwrap(), EXAMPLE.lines().map(str::trim_end).join("\n"));
+
+        for step in (0..crane.steps_done()).rev() {
+            let instr = crane.undo().unwrap();
+            assert_eq!(instr, line_to_instr(STEPS.lines().nth(step).unwrap(), &Drawing::parse(EXAMPLE).unwrap()));
+            assert_eq!(crane.drawing.stacks, states[step]);
+        }
+        assert_eq!(crane.undo(), None);
+    }
+
+    #[test]
+    fn undoing_works_with_every_mover() {
+        for capacity in 1..=4 {
+            let (crane, states) = run(BoundedMover { capacity });
+            for (step, state) in states.iter().enumerate() {
+                assert_eq!(crane.state_at(step).as_ref(), Some(state), "capacity {capacity}, step {step}");
+            }
+        }
+
+        let (mut crane, states) = run(CrateMover9001);
+        while crane.undo().is_some() {
+            assert_eq!(crane.drawing.stacks, states[crane.steps_done()]);
+        }
+    }
+
+    #[test]
+    fn bounded_mover_keeps_the_order_of_each_batch() {
+        let (mut from, mut to) = (stack("ABCDE"), stack("X"));
+        BoundedMover { capacity: 2 }.move_crates(&mut from, &mut to, 5);
+        assert_eq!((&from, &to), (&stack(""), &stack("XDEBCA")));
+
+        BoundedMover { capacity: 2 }.undo_move(&mut from, &mut to, 5);
+        assert_eq!((&from, &to), (&stack("ABCDE"), &stack("X")));
+
+        // A single crate at a time is the CrateMover 9000, and no limit is the 9001
+        for (capacity, expected) in [(1, "EDC"), (3, "CDE"), (10, "CDE")] {
+            let (mut from, mut to) = (stack("ABCDE"), vec![]);
+            BoundedMover { capacity }.move_crates(&mut from, &mut to, 3);
+            assert_eq!(to, stack(expected), "capacity {capacity}");
+        }
+    }
+
+    #[test]
+    fn rendering_and_parsing_again_gives_the_same_drawing() {
+        let drawings = [
+            // Lines without trailing spaces
+            "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3",
+            // Labels with more than one digit
+            concat!(
+                "                                        [X]\n",
+                "[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [K] [L]\n",
+                " 1   2   3   4   5   6   7   8   9   10  11  12",
+            ),
+            // Crates with more than one letter, and an empty stack
+            "[AB]\n[CD] [E]       [FGH]\n 1    2    3    4",
+        ];
+
+        for text in drawings {
+            let drawing = Drawing::parse(text).unwrap();
+            assert_eq!(Drawing::parse(&drawing.render()).unwrap(), drawing, "{text}");
+        }
+
+        let wide = Drawing::parse(drawings[1]).unwrap();
+        assert_eq!(wide.labels.last().unwrap(), "12");
+        assert_eq!(wide.stacks[10], stack("KX"));
+
+        let long = Drawing::parse(drawings[2]).unwrap();
+        assert_eq!(long.stacks, [vec!["CD", "AB"], vec!["E"], vec![], vec!["FGH"]]);
+        assert_eq!(long.render(), "[AB]\n[CD]   [E]        [FGH]\n  1     2     3     4");
+    }
+
+    #[test]
+    fn rejects_invalid_steps() {
+        let mut crane = Crane::new(CrateMover9000, Drawing::parse(EXAMPLE).unwrap());
+        assert!(crane.apply((3, 0, 1)).is_err());
+        assert!(crane.apply((1, 0, 3)).is_err());
+        assert_eq!(crane.steps_done(), 0);
+
+        crane.apply((2, 0, 0)).unwrap();
+        assert_eq!(crane.tops(), "NDP");
+    }
 }
@@ -1,20 +1,108 @@
-use itertools::Itertools;
+use std::collections::VecDeque;
+use std::io::BufRead;
+
 use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
+use crate::etc::utils::open_input;
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/** Finds the places where the last N bytes of a stream are all different.
+    The window slides one byte at a time and keeps track of how many times
+    every byte appears in it, so every step takes constant time. */
+struct MarkerDetector {
+    len: usize,
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    // Amount of bytes that appear in the window at least once
+    distinct: usize,
+}
+
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day06.txt").unwrap().chars().collect_vec();
+    let sol1 = marker_positions(open_input(6), 4).next().unwrap();
+    let sol2 = marker_positions(open_input(6), 14).next().unwrap();
 
-    let sol1 = solve_for_length(&input, 4);
-    let sol2 = solve_for_length(&input, 14);
-    
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn solve_for_length(chars: &[char], len: usize) -> usize {
-    chars.windows(len)
-         .position(|window| window.iter().unique().count() == len)
-         .unwrap() + len
+// Reads a stream of bytes and returns the position of every marker, that is,
+// the amount of bytes that had been read when the marker was completed.
+// Whitespace, such as the line break at the end of the input, is ignored.
+fn marker_positions<R: BufRead>(reader: R, len: usize) -> impl Iterator<Item = usize> {
+    let mut detector = MarkerDetector::new(len);
+
+    reader.bytes()
+        .map(|byte| byte.unwrap_or_else(|e| panic!("Can't read the datastream: {e}")))
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .enumerate()
+        .filter_map(move |(i, byte)| detector.push(byte).then_some(i + 1))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl MarkerDetector {
+    pub fn new(len: usize) -> Self {
+        assert!(len > 0, "A marker must be at least one byte long");
+        Self { len, window: VecDeque::with_capacity(len + 1), counts: [0; 256], distinct: 0 }
+    }
+
+    // Adds the next byte of the stream, returning whether the window is now a marker
+    pub fn push(&mut self, byte: u8) -> bool {
+        self.window.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+
+        if self.window.len() > self.len {
+            let old = self.window.pop_front().unwrap() as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 0 {
+                self.distinct -= 1;
+            }
+        }
+
+        self.distinct == self.len
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markers(stream: &str, len: usize) -> Vec<usize> {
+        marker_positions(stream.as_bytes(), len).collect()
+    }
+
+    #[test]
+    fn finds_the_first_marker_of_the_examples() {
+        let examples = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+
+        for (stream, packet, message) in examples {
+            assert_eq!(markers(stream, 4)[0], packet, "{stream}");
+            assert_eq!(markers(stream, 14)[0], message, "{stream}");
+        }
+    }
+
+    #[test]
+    fn finds_every_marker() {
+        assert_eq!(markers("aabbcc", 2), [3, 5]);
+        assert_eq!(markers("abcabca", 3), [3, 4, 5, 6, 7]);
+        assert_eq!(markers("aaaa", 2), []);
+        assert_eq!(markers("abc", 4), []);
+    }
+
+    #[test]
+    fn every_byte_is_a_marker_of_length_one() {
+        assert_eq!(markers("aab", 1), [1, 2, 3]);
+        // The line breaks don't count as bytes of the stream
+        assert_eq!(markers("ab\ncd\n", 4), [4]);
+    }
 }
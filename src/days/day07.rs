@@ -1,66 +1,257 @@
+use std::fmt::{Display, Formatter};
+
+use rustc_hash::FxHashMap;
 use crate::{Solution, SolutionPair};
-use std::collections::HashMap;
-use std::fs::read_to_string;
+use crate::etc::read_input;
 
 ///////////////////////////////////////////////////////////////////////////////
 
 const MAX_PART_1: u32 = 100_000;
 const TOTAL_SPACE: u32 = 70_000_000;
 const REQUIRED_SPACE: u32 = 30_000_000;
+const ROOT: DirId = 0;
+
+type DirId = usize;
+
+/** The directory tree explored during the terminal session. Directories are
+    stored in a Vec and refer to each other by their index, and since every
+    directory is created after its parent, the root is always the first one. */
+struct FileSystem {
+    dirs: Vec<Dir>,
+}
+
+struct Dir {
+    parent: Option<DirId>,
+    subdirs: FxHashMap<String, DirId>,
+    files: FxHashMap<String, u32>,
+    // Whether the contents have already been listed with ls
+    listed: bool,
+    // Size of everything inside, including subdirectories
+    total_size: u32,
+}
+
+// The output of an ls command, which is only checked once it's complete
+struct Listing {
+    dir: DirId,
+    // The line of the ls command, to point at it in errors
+    line: usize,
+    entries: FxHashMap<String, Entry>,
+}
 
-struct FileData<'a> {
-    path: Vec<&'a str>, // Doesn't include the name of the file, only the dir
-    size: u32,
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Entry {
+    Dir,
+    File(u32),
 }
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day07.txt").unwrap();
-    let files = parse(&input);
+    let input = read_input(7);
+    let fs = FileSystem::from_session(&input).unwrap_or_else(|e| panic!("{e}"));
 
-    let dir_sizes = get_dir_sizes(&files);
-    let sol1: u32 = dir_sizes.values().copied().filter(|&x| x <= MAX_PART_1).sum();
+    let sol1: u32 = fs.dir_sizes().filter(|&x| x <= MAX_PART_1).sum();
 
-    let min_size_delete = REQUIRED_SPACE - (TOTAL_SPACE - dir_sizes["/"]);
-    let sol2: u32 = dir_sizes.values().copied().filter(|&x| x >= min_size_delete).min().unwrap();
+    let min_size_delete = REQUIRED_SPACE - (TOTAL_SPACE - fs.dirs[ROOT].total_size);
+    let sol2: u32 = fs.dir_sizes().filter(|&x| x >= min_size_delete).min().unwrap();
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn get_dir_sizes(files: &[FileData]) -> HashMap<String, u32> {
-    let mut map = HashMap::new();
+///////////////////////////////////////////////////////////////////////////////
+
+impl FileSystem {
+    // Rebuilds the tree from the commands and their output. Listing the same
+    // directory more than once is fine, as long as the contents are the same.
+    pub fn from_session(session: &str) -> Result<Self, String> {
+        let mut fs = Self { dirs: vec![Dir::new(None)] };
+        let mut cwd = ROOT;
+        let mut listing: Option<Listing> = None;
+
+        for (i, line) in session.lines().enumerate() {
+            let error = |msg: String| format!("Line {}, {line:?}: {msg}", i + 1);
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            // Any command ends the previous listing
+            if line.starts_with('$') {
+                if let Some(done) = listing.take() {
+                    fs.add_listing(done)?;
+                }
+            }
+
+            match words.as_slice() {
+                ["$", "cd", "/"] => cwd = ROOT,
+                ["$", "cd", ".."] => cwd = fs.dirs[cwd].parent.ok_or_else(|| error("the root has no parent".to_owned()))?,
+                ["$", "cd", name] => cwd = fs.subdir(cwd, name).map_err(error)?,
+                ["$", "ls"] => listing = Some(Listing { dir: cwd, line: i + 1, entries: FxHashMap::default() }),
+                ["$", ..] => return Err(error("unknown command".to_owned())),
+
+                [kind, name] => {
+                    let listing = listing.as_mut().ok_or_else(|| error("not listing a directory".to_owned()))?;
+                    let entry = match *kind {
+                        "dir" => Entry::Dir,
+                        size => Entry::File(size.parse().map_err(|_| error(format!("not a valid size: {size}")))?),
+                    };
+                    if listing.entries.insert(name.to_string(), entry).is_some() {
+                        return Err(error(format!("{name} is listed more than once")));
+                    }
+                },
+                _ => return Err(error("unknown command or listing entry".to_owned())),
+            }
+        }
+
+        if let Some(done) = listing {
+            fs.add_listing(done)?;
+        }
+
+        fs.compute_sizes();
+        Ok(fs)
+    }
+
+    // The total size of every directory, including the root
+    pub fn dir_sizes(&self) -> impl Iterator<Item = u32> + '_ {
+        self.dirs.iter().map(|dir| dir.total_size)
+    }
+
+    // Returns the ID of a subdirectory, creating it if it wasn't known yet.
+    // Once a directory has been listed, all its subdirectories are known.
+    fn subdir(&mut self, parent: DirId, name: &str) -> Result<DirId, String> {
+        if let Some(&id) = self.dirs[parent].subdirs.get(name) {
+            return Ok(id);
+        }
+        if self.dirs[parent].listed {
+            return Err(format!("there is no directory {name} here"));
+        }
+
+        let id = self.dirs.len();
+        self.dirs.push(Dir::new(Some(parent)));
+        self.dirs[parent].subdirs.insert(name.to_owned(), id);
+        Ok(id)
+    }
+
+    // Fills in the contents of a directory the first time it's listed. After
+    // that, any other listing has to show exactly the same contents.
+    fn add_listing(&mut self, listing: Listing) -> Result<(), String> {
+        let error = |msg: String| format!("Line {}, listing: {msg}", listing.line);
+        let dir = &self.dirs[listing.dir];
+
+        // Everything known about the directory must be in the listing, including
+        // the subdirectories that were entered before it was first listed
+        let known = dir.subdirs.keys().map(|name| (name, Entry::Dir))
+            .chain(dir.files.iter().map(|(name, &size)| (name, Entry::File(size))));
+        for (name, entry) in known {
+            match listing.entries.get(name) {
+                Some(listed) if *listed != entry => return Err(error(format!("{name} was {entry} before, but now it's {listed}"))),
+                None => return Err(error(format!("{name} is missing"))),
+                _ => {},
+            }
+        }
+
+        if dir.listed {
+            let is_known = |name: &String| dir.subdirs.contains_key(name) || dir.files.contains_key(name);
+            return match listing.entries.keys().find(|name| !is_known(name)) {
+                Some(name) => Err(error(format!("{name} wasn't there the first time"))),
+                None => Ok(()),
+            };
+        }
+
+        for (name, entry) in listing.entries {
+            match entry {
+                Entry::Dir => { self.subdir(listing.dir, &name)?; },
+                Entry::File(size) => { self.dirs[listing.dir].files.insert(name, size); },
+            }
+        }
+        self.dirs[listing.dir].listed = true;
+        Ok(())
+    }
+
+    // Directories always come after their parents, so going backwards,
+    // every directory is complete before its size is added to its parent
+    fn compute_sizes(&mut self) {
+        for id in (0..self.dirs.len()).rev() {
+            let dir = &mut self.dirs[id];
+            dir.total_size += dir.files.values().sum::<u32>();
 
-    for FileData { path, size } in files {
-        for i in 1..=path.len() {
-            let full_path = path[..i].join("/");
-            let sum = map.entry(full_path).or_insert(0);
-            *sum += size;
+            if let Some(parent) = dir.parent {
+                let size = dir.total_size;
+                self.dirs[parent].total_size += size;
+            }
         }
     }
+}
 
-    map
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Entry::Dir => write!(f, "a directory"),
+            Entry::File(size) => write!(f, "a file of size {size}"),
+        }
+    }
 }
 
-fn parse(input: &str) -> Vec<FileData> {
-    let mut cwd = vec![];
-    let mut files = vec![];
-
-    for line in input.lines() {
-        // Remove the shell prefix if it's there and process the first word
-        let spl: Vec<&str> = line.trim_start_matches("$ ").split(' ').collect();
-
-        match spl[0] { 
-            "cd" => { // Go into the specified directory, or one up if it's ..
-                match spl[1] {
-                    ".." => cwd.truncate(cwd.len() - 1),
-                    x => cwd.push(x),
-                };
-            },
-            s if s.as_bytes()[0].is_ascii_digit() => { // The line starts with a size, add the file
-                files.push(FileData{ path: cwd.clone(), size: s.parse().unwrap() })
-            },
-            _ => {} // We can ignore everything else
-        };
+impl Dir {
+    fn new(parent: Option<DirId>) -> Self {
+        Self { parent, subdirs: FxHashMap::default(), files: FxHashMap::default(), listed: false, total_size: 0 }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n$ ls\ndir e\n29116 f\n\
+                           2557 g\n62596 h.lst\n$ cd e\n$ ls\n584 i\n$ cd ..\n$ cd ..\n$ cd d\n$ ls\n4060174 j\n\
+                           8033020 d.log\n5626152 d.ext\n7214296 k";
+
+    fn sizes(session: &str) -> Result<Vec<u32>, String> {
+        FileSystem::from_session(session).map(|fs| fs.dir_sizes().collect())
+    }
+
+    #[test]
+    fn builds_the_example_tree() {
+        let mut sizes = sizes(EXAMPLE).unwrap();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [584, 94853, 24933642, 48381165]);
+    }
+
+    #[test]
+    fn accepts_identical_listings() {
+        let again = format!("{EXAMPLE}\n$ cd /\n$ ls\n8504156 c.dat\ndir d\ndir a\n14848514 b.txt");
+        assert_eq!(sizes(&again), sizes(EXAMPLE));
+
+        // Entering a directory before listing its parent is fine too
+        assert_eq!(sizes("$ cd a\n$ ls\n1 x\n$ cd /\n$ ls\ndir a\n2 y").unwrap()[0], 3);
     }
 
-    files
-}
\ No newline at end of file
+    #[test]
+    fn rejects_different_listings() {
+        let different = [
+            // Something new
+            "$ ls\n1 a\n$ ls\n1 a\n2 b",
+            // Something missing
+            "$ ls\n1 a\n2 b\n$ ls\n1 a",
+            "$ ls\n1 a\ndir b\n$ ls\n1 a",
+            // A different size
+            "$ ls\n1 a\n$ ls\n2 a",
+            // A file that becomes a directory
+            "$ ls\n1 a\n$ ls\ndir a",
+            // A directory that was entered before, but isn't listed
+            "$ cd a\n$ cd /\n$ ls\n1 b",
+            "$ cd a\n$ cd /\n$ ls\n1 a",
+        ];
+
+        for session in different {
+            assert!(sizes(session).is_err(), "{session:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_inconsistent_entries() {
+        assert!(sizes("$ ls\n1 a\n2 a").is_err());
+        assert!(sizes("$ ls\n1 a\ndir a").is_err());
+        assert!(sizes("$ ls\n1 a\n$ cd b").is_err());
+        assert!(sizes("$ ls\n1 a\n$ cd a").is_err());
+        assert!(sizes("1 a").is_err());
+        assert!(sizes("$ cd ..").is_err());
+    }
+}
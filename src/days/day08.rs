@@ -1,21 +1,13 @@
 use crate::{Solution, SolutionPair};
 use crate::etc::vecmat::VecMat;
 use crate::etc::utils::{Pos2D, UP, DOWN, RIGHT, LEFT};
-use std::fs::read_to_string;
+use crate::etc::read_input;
 
 ///////////////////////////////////////////////////////////////////////////////
 
 pub fn solve() -> SolutionPair {
-    let data: Vec<Vec<i32>> = read_to_string("input/day08.txt").unwrap()
-        .lines()
-        .map(|line| line.chars().map(|ch| ch.to_digit(10).unwrap() as i32).collect())
-        .collect();
-
-    let height = data.len();
-    let width = data[0].len();
-
     // Dump the data into our beautiful 2D matrix structure
-    let matrix = VecMat::from_data(width, height, data.into_iter().flatten().collect());
+    let matrix = read_input(8).grid(|ch| ch.to_digit(10).unwrap() as i32);
 
     // Transform each tree into its scenic score and visibility
     let tree_data: Vec<(usize, bool)> = matrix.indexed_iter()
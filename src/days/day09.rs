@@ -2,14 +2,24 @@ use crate::{Solution, SolutionPair};
 use crate::etc::utils::{UP, DOWN, LEFT, RIGHT};
 use crate::etc::coords::Coords;
 use rustc_hash::FxHashSet;
-use std::fs::read_to_string;
+use crate::etc::read_input;
+use crate::etc::visualize::{self, Visualize};
 
 ///////////////////////////////////////////////////////////////////////////////
 
 type Instr = (Coords<i32>, u32);
 
+// Size of the area around the head that is shown when visualizing
+const VIEW_WIDTH: i32 = 80;
+const VIEW_HEIGHT: i32 = 30;
+
+struct RopeView<'a> {
+    rope: &'a [Coords<i32>],
+    visited: &'a FxHashSet<Coords<i32>>,
+}
+
 pub fn solve() -> SolutionPair {
-    let instructions: Vec<Instr> = read_to_string("input/day09.txt").unwrap()
+    let instructions: Vec<Instr> = read_input(9)
         .lines()
         .map(line_to_instr)
         .collect();
@@ -23,6 +33,7 @@ pub fn solve() -> SolutionPair {
 fn solve_for_length<const N: usize>(instrs: &[Instr]) -> usize {
     let mut visited = FxHashSet::default();
     let mut rope = [Coords::new(0, 0); N];
+    visualize::new_simulation();
 
     for (direction, times) in instrs {
         for _ in 0..*times {
@@ -33,6 +44,7 @@ fn solve_for_length<const N: usize>(instrs: &[Instr]) -> usize {
             }
 
             visited.insert(rope[N-1]);
+            visualize::show(&RopeView { rope: &rope, visited: &visited });
         }
     }
 
@@ -41,7 +53,7 @@ fn solve_for_length<const N: usize>(instrs: &[Instr]) -> usize {
 
 fn new_tail_pos(cur_head: Coords<i32>, prev_tail: Coords<i32>) -> Coords<i32> {
     match cur_head - prev_tail {
-        Coords{x, y} if x.abs() <= 1 && y.abs() <= 1 => prev_tail, // The tail is still touching
+        _ if cur_head.chebyshev_dist(&prev_tail) <= 1 => prev_tail, // The tail is still touching
         Coords{x, y} => Coords::new(prev_tail.x + x.signum(), prev_tail.y + y.signum()) // Move 1 in every direction in which the head moves
     }
 }
@@ -58,3 +70,31 @@ pub fn line_to_instr(line: &str) -> Instr {
 
     (Coords::from(dir), n.parse().unwrap())
 }
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl Visualize for RopeView<'_> {
+    // The head is H and the rest of the knots are numbered, the
+    // positions visited by the tail are marked with #
+    fn frame(&self) -> String {
+        let head = self.rope[0];
+        let top_left = head - Coords::new(VIEW_WIDTH / 2, VIEW_HEIGHT / 2);
+
+        (0..VIEW_HEIGHT).map(|dy| {
+            (0..VIEW_WIDTH).map(|dx| {
+                let pos = top_left + Coords::new(dx, dy);
+                match self.rope.iter().position(|&knot| knot == pos) {
+                    Some(0) => 'H',
+                    Some(i) => char::from_digit(i as u32 % 10, 10).unwrap(),
+                    None if pos == Coords::new(0, 0) => 's',
+                    None if self.visited.contains(&pos) => '#',
+                    None => '.',
+                }
+            }).collect::<String>()
+        }).collect::<Vec<_>>().join("\n")
+    }
+
+    fn caption(&self) -> String {
+        format!("Rope with {} knots, the tail has visited {} positions", self.rope.len(), self.visited.len())
+    }
+}
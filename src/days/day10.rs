@@ -1,11 +1,11 @@
 use crate::{Solution, SolutionPair};
 use itertools::Itertools;
-use std::fs::read_to_string;
+use crate::etc::read_input;
 
 ///////////////////////////////////////////////////////////////////////////////
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day10.txt").unwrap();
+    let input = read_input(10);
 
     let mut reg = 1;
     let mut cycle = 1;
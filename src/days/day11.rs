@@ -1,8 +1,10 @@
-use crate::etc::utils::DOUBLE_NEWLINE;
+use crate::etc::checked;
+use crate::etc::parsing::Pattern;
 use crate::{Solution, SolutionPair};
 use itertools::Itertools;
+use lazy_static::lazy_static;
 use std::collections::VecDeque;
-use std::fs::read_to_string;
+use crate::etc::read_input;
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -23,9 +25,9 @@ enum Operation {
 }
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day11.txt").unwrap();
+    let input = read_input(11);
 
-    let mut monkeys_p1 = input.split(DOUBLE_NEWLINE).map(Monkey::from_str).collect_vec();
+    let mut monkeys_p1 = input.paragraphs().map(Monkey::from_str).collect_vec();
     let mut monkeys_p2 = monkeys_p1.clone();
 
     let sol1 = solve_for_params(&mut monkeys_p1, 20, 3);
@@ -35,12 +37,15 @@ pub fn solve() -> SolutionPair {
 }
 
 fn solve_for_params(monkeys: &mut [Monkey], iters: u32, worry_red: i64) -> u64 {
-    let modulus: i64 = monkeys.iter().map(|x| x.divide_by).product();
+    let modulus: i64 = monkeys.iter()
+        .map(|x| x.divide_by)
+        .fold(1, |acc, x| checked::mul(acc, x, || "day 11, computing the modulus".to_owned()));
 
     // Do some monkey business
-    for _ in 0..iters {
+    for round in 1..=iters {
         for i in 0..monkeys.len() {
-            while let Some((mut item, target)) = monkeys[i].process_next(worry_red) {
+            let context = || format!("day 11, round {round}, monkey {i}");
+            while let Some((mut item, target)) = monkeys[i].process_next(worry_red, context) {
                 if worry_red == 1 { item %= modulus };
                 monkeys[target].items.push_back(item);
             }
@@ -50,29 +55,33 @@ fn solve_for_params(monkeys: &mut [Monkey], iters: u32, worry_red: i64) -> u64 {
     // Look for the top 2 monkeys to get the solution
     let mut inspections = monkeys.iter().map(|x| x.inspected).collect_vec();
     inspections.sort_by(|a, b| b.cmp(a));
-    inspections[0] * inspections[1]
+    checked::mul(inspections[0], inspections[1], || "day 11, computing the monkey business".to_owned())
 }
 
 impl Monkey {
     // Parse monkey data from lines
     pub fn from_str(string: &str) -> Self {
-        let lines = string.lines().collect_vec();
+        lazy_static! {
+            static ref MONKEY: Pattern = Pattern::new(
+                "Monkey {_} Starting items: {str} Operation: new = {str} Test: divisible by {i64} \
+                 If true: throw to monkey {usize} If false: throw to monkey {usize}"
+            );
+        }
 
-        let items = lines[1][18..].split(", ").map(|x| x.parse().unwrap()).collect();
-        let update_op = Operation::from_str(&lines[2][19..]);
-        let divide_by = lines[3][21..].parse().unwrap();
-        let if_true = lines[4][29..].parse().unwrap();
-        let if_false = lines[5][30..].parse().unwrap();
+        let (items, operation, divide_by, if_true, if_false): (String, String, _, _, _) = MONKEY.parse(string).unwrap();
+        let items = items.split(',').map(|x| x.trim().parse().unwrap()).collect();
+        let update_op = Operation::from_str(&operation);
 
         Self { items, update_op, divide_by, targets: [if_false, if_true], inspected: 0 }
     }
 
     // Processes the first item in the list, and returns its new worry value
     // and the monkey to throw it to. Returns None if we have no items left.
-    pub fn process_next(&mut self, relief_ratio: i64) -> Option<(i64, usize)> {
+    pub fn process_next<F>(&mut self, relief_ratio: i64, context: F) -> Option<(i64, usize)>
+    where F: FnOnce() -> String {
         self.items.pop_front().map(|item| {
             self.inspected += 1;
-            let new_worry = self.update_op.apply(item) / relief_ratio;
+            let new_worry = self.update_op.apply(item, context) / relief_ratio;
             let test = new_worry % self.divide_by == 0;
             let target = self.targets[test as usize];
             (new_worry, target)
@@ -91,11 +100,12 @@ impl Operation {
         }
     }
 
-    pub fn apply(&self, item: i64) -> i64 {
+    pub fn apply<F>(&self, item: i64, context: F) -> i64
+    where F: FnOnce() -> String {
         match self {
-            Self::Pow => item * item,
-            Self::Add(x) => item + x,
-            Self::Mul(x) => item * x
+            Self::Pow => checked::mul(item, item, context),
+            Self::Add(x) => checked::add(item, *x, context),
+            Self::Mul(x) => checked::mul(item, *x, context)
         }
     }
 }
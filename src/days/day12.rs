@@ -1,64 +1,39 @@
-use itertools::Itertools;
-use rustc_hash::FxHashMap;
-
 use crate::{Solution, SolutionPair};
 use crate::etc::vecmat::VecMat;
 use crate::etc::utils::{UP, DOWN, LEFT, RIGHT};
 use crate::etc::coords::Coords;
+use crate::etc::search::bfs_multi;
+use crate::etc::image;
 
-use std::collections::BinaryHeap;
-use std::fs::read_to_string;
+use crate::etc::utils::{read_input, Input};
 
 ///////////////////////////////////////////////////////////////////////////////
 
 type Pos = Coords<i32>;
 
-#[derive(Copy, Clone, Eq)]
-struct SearchState {
-    pub cost: u32,
-    pub node: Pos,
-}
-
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day12.txt").unwrap();
+    let input = read_input(12);
     let (heights, start, end) = parse(&input);
+    // The higher the brighter, from 0 to 250
+    image::save_output("day12_heights", || heights.to_image(4, |height| height * 10));
 
-    let sol1 = shortest_path(start, end, &heights);
-    let sol2 = heights.indexed_iter()
-                      .filter(|(_, val)| *val == 0)
-                      .map(|(pos, _)| shortest_path(Pos::new(pos.0 as i32, pos.1 as i32), end, &heights))
-                      .min().unwrap();
+    let sol1 = shortest_path([start], end, &heights);
+
+    // Part 2: searching from all the lowest points at once is the same
+    // as searching from each one of them and keeping the best result
+    let lowest = heights.indexed_iter()
+                        .filter(|(_, val)| *val == 0)
+                        .map(|(pos, _)| Pos::from_pos2d(pos));
+    let sol2 = shortest_path(lowest, end, &heights);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn shortest_path(source: Pos, target: Pos, heights: &VecMat<u8>) -> u32 {
-    let mut to_visit = BinaryHeap::new();
-    let mut visited_costs = FxHashMap::default();
-
-    to_visit.push(SearchState{ node: source, cost: 0 });
-
-    while let Some(SearchState { node, cost }) = to_visit.pop() {
-        if node == target {
-            return cost;
-        }
-
-        for next_node in ok_neighbors(node, heights) {
-            let new_cost = cost + 1;
-            let prev_cost = match visited_costs.get(&next_node) {
-                Some(val) => *val,
-                None => u32::MAX,
-            };
-
-            if new_cost < prev_cost {
-                visited_costs.insert(next_node, new_cost);
-                to_visit.push(SearchState { cost: new_cost, node: next_node });
-            }
-
-        }
-    }
-
-    u32::MAX
+fn shortest_path<I>(sources: I, target: Pos, heights: &VecMat<u8>) -> usize
+where I: IntoIterator<Item = Pos> {
+    bfs_multi(sources, |node| ok_neighbors(node, heights), |node| node == target)
+        .cost()
+        .unwrap()
 }
 
 fn ok_neighbors(pos: Pos, mat: &VecMat<u8>) -> Vec<Pos> {
@@ -70,51 +45,16 @@ fn ok_neighbors(pos: Pos, mat: &VecMat<u8>) -> Vec<Pos> {
         .collect()
 }
 
-fn parse(input: &str) -> (VecMat<u8>, Pos, Pos) {
-    let lines = input.lines().collect_vec();
-    let height = lines.len();
-    let width = lines[0].len();
-
-    let mut mat = VecMat::new(width, height, 0);
-    let mut start = Pos::new(0, 0);
-    let mut end = Pos::new(0, 0);
-
-    for (y, line) in lines.iter().enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            let val = match ch {
-                'a'..='z' => ch as u8 - b'a',
-                'S' => {
-                    start = Pos::new(x as i32, y as i32);
-                    0
-                },
-                'E' => {
-                    end = Pos::new(x as i32, y as i32);
-                    b'z' - b'a'
-                },
-                _ => unreachable!()
-            };
+fn parse(input: &Input) -> (VecMat<u8>, Pos, Pos) {
+    let chars = input.grid(|ch| ch);
+    let find = |target| chars.indexed_iter().find(|(_, ch)| *ch == target).map(|(pos, _)| Pos::from_pos2d(pos)).unwrap();
 
-            mat[(x, y)] = val;
-        }
-    }
-
-    (mat, start, end)
-}
-
-impl PartialEq for SearchState {
-    fn eq(&self, other: &Self) -> bool {
-        self.cost == other.cost
-    }
-}
-
-impl PartialOrd for SearchState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
+    let heights = input.grid(|ch| match ch {
+        'a'..='z' => ch as u8 - b'a',
+        'S' => 0,
+        'E' => b'z' - b'a',
+        _ => unreachable!()
+    });
 
-impl Ord for SearchState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.cost.cmp(&self.cost)
-    }
+    (heights, find('S'), find('E'))
 }
\ No newline at end of file
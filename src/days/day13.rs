@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use crate::{Solution, SolutionPair};
 use std::cmp::{Ordering, PartialOrd, Ord};
-use std::fs::read_to_string;
+use crate::etc::read_input;
 use PacketElem::*;
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -13,7 +13,7 @@ enum PacketElem {
 }
 
 pub fn solve() -> SolutionPair {
-    let mut packets = read_to_string("input/day13.txt").unwrap()
+    let mut packets = read_input(13)
         .lines()
         .filter(|line| !line.is_empty())
         .map(parse_packet)
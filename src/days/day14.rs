@@ -1,12 +1,24 @@
 use itertools::Itertools;
-use rustc_hash::FxHashSet;
-use crate::etc::coords::Coords;
+use crate::etc::{Coords, SparseGrid};
 use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
+use crate::etc::read_input;
+use crate::etc::image::{self, Rgb};
+use crate::etc::visualize::{self, Visualize};
 
 ///////////////////////////////////////////////////////////////////////////////
 
 type Pos = Coords<i32>;
+type CaveMap = SparseGrid<i32, Tile>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Tile {
+    Rock, Sand
+}
+
+struct CaveView<'a> {
+    map: &'a CaveMap,
+    resting: usize,
+}
 
 pub const SPAWN: Pos = Pos::new(500, 0);
 pub const DOWN: Pos = Pos::new(0, 1);
@@ -14,8 +26,8 @@ pub const DOWN_LEFT: Pos = Pos::new(-1, 1);
 pub const DOWN_RIGHT: Pos = Pos::new(1, 1);
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day14.txt").unwrap();
-    let mut map = FxHashSet::default(); 
+    let input = read_input(14);
+    let mut map = CaveMap::new();
 
     input.lines().for_each(|line| add_rocks(line, &mut map));
     
@@ -25,10 +37,11 @@ pub fn solve() -> SolutionPair {
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn simulate(map: &FxHashSet<Pos>, bottomless: bool) -> usize {
+fn simulate(map: &CaveMap, bottomless: bool) -> usize {
     let mut map = map.clone();
     let n_rocks = map.len();
-    let max_y = map.iter().map(|pos| pos.y).max().unwrap();
+    let max_y = map.bounds().unwrap().1.y;
+    visualize::new_simulation();
 
     let mut overflowing = false;
 
@@ -50,7 +63,8 @@ fn simulate(map: &FxHashSet<Pos>, bottomless: bool) -> usize {
                 }
             } else {
                 // No possible movements, the particle comes to a stop where it is
-                map.insert(particle_pos);
+                map.insert(particle_pos, Tile::Sand);
+                visualize::show(&CaveView { map: &map, resting: map.len() - n_rocks });
                 break;
             }
         }
@@ -61,18 +75,43 @@ fn simulate(map: &FxHashSet<Pos>, bottomless: bool) -> usize {
         }
     }
 
+    let name = if bottomless { "day14_part1" } else { "day14_part2" };
+    image::save_output(name, || map.to_image(2, tile_color));
+
     map.len() - n_rocks
 }
 
-fn add_rocks(line: &str, map: &mut FxHashSet<Pos>) {
+fn tile_color(tile: Option<&Tile>) -> Rgb {
+    match tile {
+        Some(Tile::Rock) => [96, 96, 96],
+        Some(Tile::Sand) => [230, 190, 100],
+        None => [16, 16, 32],
+    }
+}
+
+fn add_rocks(line: &str, map: &mut CaveMap) {
     line.split(" -> ").map(|coords| {
         let mut spl = coords.split(',').map(|v| v.parse().unwrap());
         Pos::new(spl.next().unwrap(), spl.next().unwrap())
     })
     .tuple_windows()
     .for_each(|(start, end)| {
-        start.iter_to(&end).for_each(|pos| {
-            map.insert(pos);
-        });
+        map.extend(start.iter_to(&end).map(|pos| (pos, Tile::Rock)));
     });
 }
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl Visualize for CaveView<'_> {
+    fn frame(&self) -> String {
+        self.map.render(|tile| match tile {
+            Some(Tile::Rock) => '#',
+            Some(Tile::Sand) => 'o',
+            None => '.',
+        })
+    }
+
+    fn caption(&self) -> String {
+        format!("{} units of sand at rest", self.resting)
+    }
+}
@@ -1,72 +1,90 @@
 use itertools::Itertools;
-use rustc_hash::{FxHashMap, FxHashSet};
-use regex::Regex;
+use rustc_hash::FxHashMap;
 use lazy_static::lazy_static;
-use petgraph::algo::dijkstra;
-use petgraph::graph::DiGraph;
 
 use crate::etc::id_assigner::IDAssigner;
+use crate::etc::search::bfs;
+use crate::etc::parsing::Pattern;
 use crate::{Solution, SolutionPair};
-use std::cmp::max;
-use std::fs::read_to_string;
+use crate::etc::read_input;
 
 ////////////////////////////////////////////////////////////////////////////////
 
-type ValveGraph = DiGraph<u32, ()>;
-
 struct ValveInfo {
     flow_rate: u32,
     connections: Vec<u32>,
 }
 
+// A way to spend the time: the pressure released and the valves opened, in order
+#[derive(Clone, Default)]
+struct Plan {
+    pressure: u32,
+    valves: Vec<u32>,
+}
+
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day16.txt").unwrap();
+    let input = read_input(16);
     let mut id_assigner = IDAssigner::new();
 
     let valves_data: FxHashMap<u32, ValveInfo> = input.lines().map(|line| parse_line(line, &mut id_assigner)).collect();
+    let names = id_assigner.freeze();
+    let start = names.id_of("AA").unwrap();
     let nonzero_valves = valves_data.iter().filter(|(_, v)| v.flow_rate > 0).map(|(k, _)| *k).collect_vec();
     
-    // Construct a graph out of the valves, to compute the path length matrix
-    let edges = get_edges(&valves_data);
-    let graph = ValveGraph::from_edges(&edges);
-    let min_paths = compute_min_paths(&graph);
+    // Compute the path length matrix between every pair of valves
+    let min_paths = compute_min_paths(&valves_data);
    
-    let sol1 = find_solution(30, &min_paths, &valves_data, &nonzero_valves, u64::MAX);
-    let mut sol2 = 0;
+    let plan1 = find_solution(start, 30, &min_paths, &valves_data, &nonzero_valves, u64::MAX);
+    let mut plan2 = (Plan::default(), Plan::default());
 
     for bitmask in 0..2_u64.pow(nonzero_valves.len() as u32 - 1) {
-        let human_score = find_solution(26, &min_paths, &valves_data, &nonzero_valves, bitmask);
-        let elephant_score = find_solution(26, &min_paths, &valves_data, &nonzero_valves, !bitmask);
-        sol2 = max(sol2, human_score + elephant_score);
+        let human_plan = find_solution(start, 26, &min_paths, &valves_data, &nonzero_valves, bitmask);
+        let elephant_plan = find_solution(start, 26, &min_paths, &valves_data, &nonzero_valves, !bitmask);
+        if human_plan.pressure + elephant_plan.pressure > plan2.0.pressure + plan2.1.pressure {
+            plan2 = (human_plan, elephant_plan);
+        }
     }
 
-    (Solution::from(sol1), Solution::from(sol2))
+    // Also tell which valves are opened, and in which order
+    let show = |plan: &Plan| plan.valves.iter().map(|&id| &names[id]).join(", ");
+    let sol1 = Solution::from(plan1.pressure).with_note(format!("opening {}", show(&plan1)));
+    let sol2 = Solution::from(plan2.0.pressure + plan2.1.pressure)
+        .with_note(format!("opening {}, and the elephant {}", show(&plan2.0), show(&plan2.1)));
+
+    (sol1, sol2)
 }
 
 fn find_solution(
+    start: u32,
     time: u32, 
     path_lengths: &FxHashMap<(u32, u32), u32>, 
     valves_data: &FxHashMap<u32, ValveInfo>, 
     possible_valves: &[u32],
     bitmask: u64,
-) -> u32 {
-    find_optimal((0, 0, time), &mut FxHashSet::default(), path_lengths, valves_data, possible_valves, bitmask)
+) -> Plan {
+    let mut best = Plan::default();
+    find_optimal((start, 0, time), &mut vec![], path_lengths, valves_data, possible_valves, bitmask, &mut best);
+    best
 }
 
 fn find_optimal(
     (current_position, acc_pressure, time_remaining): (u32, u32, u32),
-    opened: &mut FxHashSet<u32>,
+    opened: &mut Vec<u32>,
     path_lengths: &FxHashMap<(u32, u32), u32>,
     valves_data: &FxHashMap<u32, ValveInfo>,
     possible_valves: &[u32],
-    bitmask: u64
-) -> u32 {
+    bitmask: u64,
+    best: &mut Plan,
+) {
+    if acc_pressure > best.pressure {
+        best.pressure = acc_pressure;
+        best.valves.clone_from(opened);
+    }
+
     // Stop if we have no time left or no valves left to open
     if time_remaining == 0 || opened.len() == possible_valves.len() {
-        return acc_pressure;
+        return;
     }
-    
-    let mut best_score = acc_pressure;
 
     // Try the remaining valves
     for (i, &dest) in possible_valves.iter().enumerate() {
@@ -80,50 +98,39 @@ fn find_optimal(
 
         // Go there, update the remaining time and compute how much pressure we got
         let new_time = time_remaining - travel_time - 1;
-        opened.insert(dest);
+        opened.push(dest);
         let added_score = valves_data[&dest].flow_rate * new_time;
 
         // Recursively search for the optimal solution from here
-        let score = find_optimal((dest, acc_pressure + added_score, new_time), opened, path_lengths, valves_data, possible_valves, bitmask);
-        best_score = max(score, best_score);
+        find_optimal((dest, acc_pressure + added_score, new_time), opened, path_lengths, valves_data, possible_valves, bitmask, best);
 
         // Remove the visited valve after the recursive call
-        opened.remove(&dest);
+        opened.pop();
     }
-
-    best_score
 }
 
-fn compute_min_paths(graph: &ValveGraph) -> FxHashMap<(u32, u32), u32> {
+fn compute_min_paths(valves_data: &FxHashMap<u32, ValveInfo>) -> FxHashMap<(u32, u32), u32> {
     let mut paths = FxHashMap::default();
 
-    for origin in graph.node_indices() {
-        for (dest, length) in dijkstra(graph, origin, None, |_| 1) {
-            let key = (origin.index() as u32, dest.index() as u32);
-            paths.insert(key, length as u32);
+    for &origin in valves_data.keys() {
+        let neighbors = |valve| valves_data[&valve].connections.iter().copied();
+        for (dest, length) in bfs(origin, neighbors, |_| false).distances {
+            paths.insert((origin, dest), length as u32);
         }
     }
 
     paths
 }
 
-fn get_edges(valves_data: &FxHashMap<u32, ValveInfo>) -> Vec<(u32, u32)> {
-    valves_data.iter().flat_map(|(id, data)| {
-        data.connections.iter().map(|conn| (*id, *conn))
-    }).collect_vec()
-}
-
 fn parse_line(line: &str, id_assigner: &mut IDAssigner<String>) -> (u32, ValveInfo) {
     lazy_static! {
-        static ref RE: Regex = Regex::new(r"Valve (.*) has flow rate=(\d*); tunnels? leads? to valves? (.*)").unwrap();
+        // The wording changes depending on the amount of tunnels, so it's skipped
+        static ref VALVE: Pattern = Pattern::new("Valve {word} has flow rate={u32}; {_} {_} to {_} {str}");
     }
 
-    let groups = RE.captures(line).unwrap();
-    let valve_name = groups.get(1).unwrap().as_str();
-    let flow_rate = groups.get(2).unwrap().as_str().parse().unwrap();
-    let conn_names = groups.get(3).unwrap().as_str();
+    let (valve_name, flow_rate, conn_names): (String, u32, String) = VALVE.parse(line).unwrap();
 
-    let valve_id = id_assigner.get_id(valve_name.to_owned());
+    let valve_id = id_assigner.get_id(valve_name);
     let connections = conn_names.split(", ").map(|spl| id_assigner.get_id(spl.to_string())).collect();
 
     let valve_info = ValveInfo { flow_rate, connections };
@@ -1,22 +1,25 @@
-use std::cmp::max;
-use std::fs::read_to_string;
-use std::hash::{Hash, Hasher};
+use std::iter;
 
 use itertools::Itertools;
-use rustc_hash::FxHashSet;
 
 use crate::{Solution, SolutionPair};
-use crate::etc::coords::Coords;
+use crate::etc::read_input;
+use crate::etc::{Coords, SparseGrid};
+use crate::etc::cycle::{self, Cycle};
+use crate::etc::visualize::{self, Visualize};
 use Direction::*;
 
 ///////////////////////////////////////////////////////////////////////////////
 
 type Pos = Coords<i64>;
-type CaveMap = FxHashSet<Pos>;
+type CaveMap = SparseGrid<i64>;
 
 const ROCK_TYPES: [char; 5] = ['-', '+', 'L', 'I', 'O'];
-const ROCKS_P1: i64 = 2022;
-const ROCKS_P2: i64 = 1000000000000;
+const ROCKS_P1: usize = 2022;
+const ROCKS_P2: usize = 1000000000000;
+const WIDTH: usize = 7;
+// Amount of rows at the top of the tower that are shown when visualizing
+const VIEW_HEIGHT: i64 = 40;
 
 struct Rock {
     coords: Vec<Pos>,
@@ -25,17 +28,22 @@ struct Rock {
     bottom_hitbox: Vec<usize>,
 }
 
-// Struct to store some info about the moment when a rock lands, to help with
-// detecting a repetition pattern. This struct also stores the rock index but
-// it's not hashed or compared for equality by it.
-#[derive(Copy, Clone)]
-struct RockLanding {
-    i: i64,
-    rock_type: char,
-    x_pos: i64,
-    wind_moment: usize,
+struct Chamber<'a> {
+    cave: CaveMap,
+    jetstreams: &'a [Direction],
+    next_rock: usize,
+    next_jet: usize,
+    rocks_dropped: usize,
 }
 
+// What the chamber looks like right before a rock spawns: which rock and jet
+// come next, and which empty cells a rock could still get into, as a bitmask
+// per row starting at the top of the tower. Every cell of a falling rock only
+// moves left, right or down through empty cells, so the rest of the cave can
+// never make a difference. This is all the future of the simulation depends
+// on, so once it repeats, so will the growth of the tower.
+type ChamberState = (usize, usize, Vec<u8>);
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum Direction {
     Left, Right
@@ -44,111 +52,129 @@ enum Direction {
 ///////////////////////////////////////////////////////////////////////////////
 
 pub fn solve() -> SolutionPair {
-    let jet_patterns = read_to_string("input/day17.txt").unwrap()
-        .chars()
+    let jet_patterns = parse_jets(&read_input(17));
+    let cycle = find_cycle(&jet_patterns);
+
+    // Every repetition of the cycle grows the tower by the same amount
+    let sol1 = cycle.extrapolate(ROCKS_P1, || format!("day 17, dropping {ROCKS_P1} rocks"));
+    let sol2 = cycle.extrapolate(ROCKS_P2, || format!("day 17, dropping {ROCKS_P2} rocks"));
+
+    (Solution::from(sol1), Solution::from(sol2))
+}
+
+fn parse_jets(text: &str) -> Vec<Direction> {
+    text.chars()
         .map(|c| match c {
             '>' => Right,
             '<' => Left,
              _  => unreachable!()
-        }).collect_vec();
-
-    // Part 1: Just run the simulation normally for the first 2022 rocks
-    let sol1 = run_simulation(&jet_patterns, 0, ROCKS_P1, &mut None);
-
-    // Part 2: First, determine the moment when the repetition starts happening
-    let mut rep_i = [0; 2];
-    run_simulation(&jet_patterns, 0, i64::MAX, &mut Some(&mut rep_i));
-    let [cycle_start, cycle_end] = rep_i;
-
-    // Determine how many times the cycle repeat and what's the leftover
-    let in_cycle = ROCKS_P2 - cycle_start;
-    let cycle_len = cycle_end - cycle_start;
-    let (reps, remainder) = (in_cycle / cycle_len, in_cycle % cycle_len);
-
-    // Calculate the total height: up until the first end of the cycle, the
-    // remaining iterations, and the final part
-    let h1 = run_simulation(&jet_patterns, 0, cycle_end, &mut None);
-    let h2 = run_simulation(&jet_patterns, cycle_end, cycle_end + cycle_len, &mut None) * (reps-1);
-    let h3 = run_simulation(&jet_patterns, cycle_end, cycle_end + remainder, &mut None);
-
-    let sol2 = h1 + h2 + h3;
+        }).collect_vec()
+}
 
-    (Solution::from(sol1), Solution::from(sol2))
+// Drops rocks until the chamber is in a state it was already in, keeping
+// track of the tower's height after every rock
+fn find_cycle(jet_patterns: &[Direction]) -> Cycle<i64> {
+    let mut chamber = Chamber::new(jet_patterns);
+    visualize::new_simulation();
+    let states = iter::repeat_with(|| {
+        let state = (chamber.state(), chamber.height());
+        chamber.drop_rock();
+        visualize::show(&chamber);
+        state
+    });
+    cycle::find_by_hash(states).unwrap()
 }
 
-fn run_simulation(jetstreams: &[Direction], start: i64, end: i64, cycle_indices: &mut Option<&mut [i64]>) -> i64 {
-    if start >= end {
-        return 0;
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a> Chamber<'a> {
+    pub fn new(jetstreams: &'a [Direction]) -> Self {
+        // Populate the cave's floor
+        let cave = (0..WIDTH as i64).map(|x| Pos::new(x, -1)).collect();
+        Self { cave, jetstreams, next_rock: 0, next_jet: 0, rocks_dropped: 0 }
     }
 
-    let mut cave = FxHashSet::default();
-    let mut states: FxHashSet<RockLanding> = FxHashSet::default();
+    pub fn height(&self) -> i64 {
+        self.cave.bounds().unwrap().1.y + 1
+    }
 
-    // Populate the cave's floor
-    for x in 0..7 {
-        cave.insert(Pos::new(x, -1));
+    pub fn state(&self) -> ChamberState {
+        (self.next_rock, self.next_jet, self.reachable_rows())
     }
 
-    let mut rock_types = ROCK_TYPES.iter().cycle();
-    let mut jet_directions = jetstreams.iter().enumerate().cycle().peekable();
-    let mut repetitions = 0;
-    let mut start_y = 0;
-    let mut max_y = -1;
+    // Goes down the tower row by row, starting above it where every cell can be
+    // reached. A cell can be reached if it's empty and the one above it can, or if
+    // it's next to one that can. The floor makes sure that this eventually ends.
+    fn reachable_rows(&self) -> Vec<u8> {
+        let full_row = (1 << WIDTH) - 1;
+        let mut rows = vec![];
+        let mut reachable = full_row;
+
+        for y in (0..self.height()).rev() {
+            let empty = (0..WIDTH).filter(|&x| !self.cave.contains(&Pos::new(x as i64, y)))
+                                  .fold(0, |mask, x| mask | 1 << x);
+
+            reachable &= empty;
+            loop {
+                let spread = (reachable | reachable << 1 | reachable >> 1) & empty & full_row;
+                if spread == reachable {
+                    break;
+                }
+                reachable = spread;
+            }
 
-    for i in 0..end {
-        if i == start {
-            start_y = max_y;
+            if reachable == 0 {
+                break;
+            }
+            rows.push(reachable);
         }
 
+        rows
+    }
+
+    pub fn drop_rock(&mut self) {
         // Spawn a new rock, determining its initial position
-        let mut still_moving = true;
-        let rock_type = *rock_types.next().unwrap();
-        let y_spawn = max_y + 4;
-        let mut rock = Rock::spawn_new(Pos::new(2, y_spawn), rock_type);
+        let mut rock = Rock::spawn_new(Pos::new(2, self.height() + 3), ROCK_TYPES[self.next_rock]);
+        self.next_rock = (self.next_rock + 1) % ROCK_TYPES.len();
 
         // Simulate the rock's movement through the cave
+        let mut still_moving = true;
         while still_moving {
-            let jetstream = jet_directions.next().unwrap().1;
-            rock.move_horizontally(*jetstream, &cave);
-            still_moving = rock.move_down(&cave);
-        }
-
-        // If we are looking for the moment when the cycle starts, update the set
-        // with the info on this rock's landing instant
-        if let Some(ptr) = cycle_indices {
-            let x_pos = rock.coords[0].x;
-            let wind_moment = jet_directions.peek().unwrap().0;
-            let state = RockLanding { i, x_pos, wind_moment, rock_type };
-
-            // Has the state occured before?
-            if let Some(prev_state) = states.get(&state) {
-                repetitions += 1;
-                // Wait until we have 5 repetitions in a row to consider the cycle started
-                if repetitions == 5 {
-                    ptr[0] = prev_state.i - 4;
-                    ptr[1] = i - 4;
-                    break;
-                }
-            } else {
-                // Otherwise, just store this state, reset the repetition counter and keep going
-                repetitions = 0;
-                states.insert(state);
-            }
+            rock.move_horizontally(self.jetstreams[self.next_jet], &self.cave);
+            self.next_jet = (self.next_jet + 1) % self.jetstreams.len();
+            still_moving = rock.move_down(&self.cave);
         }
 
         // The rock stopped moving, add its positions to the cave map
-        max_y = max(max_y, rock.coords.last().unwrap().y);
+        self.cave.extend(rock.coords);
+        self.rocks_dropped += 1;
+    }
+}
 
-        for pos in rock.coords.into_iter() {
-            cave.insert(pos);
+impl Visualize for Chamber<'_> {
+    // Draws the top of the tower, with the floor when it's close enough
+    fn frame(&self) -> String {
+        let top = self.height() - 1;
+        let rows = (top - VIEW_HEIGHT + 1).max(0)..=top;
+
+        let mut lines = rows.rev().map(|y| {
+            let cells: String = (0..WIDTH as i64)
+                .map(|x| if self.cave.contains(&Pos::new(x, y)) { '#' } else { '.' })
+                .collect();
+            format!("|{cells}|")
+        }).collect_vec();
+
+        if top < VIEW_HEIGHT {
+            lines.push(format!("+{}+", "-".repeat(WIDTH)));
         }
+        lines.join("\n")
     }
 
-    max_y - start_y
+    fn caption(&self) -> String {
+        format!("{} rocks dropped, the tower is {} units tall", self.rocks_dropped, self.height())
+    }
 }
 
-///////////////////////////////////////////////////////////////////////////////
-
 impl Rock {
     // Creates a new rock of a certain type spawning in the given position
     pub fn spawn_new(bottom_left: Pos, rock_type: char) -> Self {
@@ -220,17 +246,44 @@ impl Rock {
 
 ///////////////////////////////////////////////////////////////////////////////
 
-impl Eq for RockLanding { }
-impl PartialEq for RockLanding {
-    fn eq(&self, other: &Self) -> bool {
-        self.rock_type == other.rock_type && self.x_pos == other.x_pos && self.wind_moment == other.wind_moment
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    #[test]
+    fn solves_the_example() {
+        let cycle = find_cycle(&parse_jets(EXAMPLE));
+        assert_eq!(cycle.extrapolate(ROCKS_P1, String::new), 3068);
+        assert_eq!(cycle.extrapolate(ROCKS_P2, String::new), 1514285714288);
     }
-}
 
-impl Hash for RockLanding {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.rock_type.hash(state);
-        self.x_pos.hash(state);
-        self.wind_moment.hash(state);
+    #[test]
+    fn extrapolation_matches_the_simulation() {
+        let jets = parse_jets(EXAMPLE);
+        let cycle = find_cycle(&jets);
+        let mut chamber = Chamber::new(&jets);
+
+        for rocks in 0..5000 {
+            assert_eq!(cycle.extrapolate(rocks, String::new), chamber.height(), "after {rocks} rocks");
+            chamber.drop_rock();
+        }
+    }
+
+    #[test]
+    fn holes_under_overhangs_are_part_of_the_state() {
+        let jets = parse_jets(EXAMPLE);
+        let mut chamber = Chamber::new(&jets);
+        chamber.cave.extend([Pos::new(1, 1), Pos::new(2, 1), Pos::new(3, 0), Pos::new(3, 1)]);
+
+        // The cells under the overhang can be reached through the gap by the wall,
+        // even though the column heights alone would say they're full
+        let (_, _, rows) = chamber.state();
+        assert_eq!(rows, [0b1110001, 0b1110111]);
+
+        // Once the gap is closed they can never be reached again
+        chamber.cave.insert(Pos::new(0, 1), ());
+        assert_eq!(chamber.state().2, [0b1110000, 0b1110000]);
     }
 }
@@ -1,98 +1,55 @@
-use std::fs::read_to_string;
-use std::cmp::{min, max};
-
-use rustc_hash::FxHashSet;
+use itertools::Itertools;
 
 use crate::{Solution, SolutionPair};
+use crate::etc::read_input;
+use crate::etc::voxel_grid::{Voxel, VoxelGrid};
+use crate::etc::image::{self, FrameWriter};
 
 ///////////////////////////////////////////////////////////////////////////////
 
-type Coords3D = [i32; 3];
-type CoordsSet = FxHashSet<Coords3D>;
-const DELTAS: [Coords3D; 6] = [[1, 0, 0], [-1, 0, 0], [0, 1, 0], [0, -1, 0], [0, 0, 1], [0, 0, -1]];
-
 pub fn solve() -> SolutionPair {
-    let cubes: CoordsSet = read_to_string("input/day18.txt").unwrap()
+    let cubes = read_input(18)
         .lines()
-        .map(|line| line.split(',').map(|x| x.parse().unwrap()).collect::<Vec<_>>().try_into().unwrap())
-        .collect();
-
-    let sol1: usize = cubes.iter().map(|cube| visible_sides(*cube, &cubes)).sum();
-    let sol2 = expand_gas(&cubes);
+        .map(|line| {
+            let axes: [i32; 3] = line.split(',').map(|x| x.parse().unwrap()).collect_vec().try_into().unwrap();
+            Voxel::from(axes)
+        })
+        .collect_vec();
+
+    // Place the cubes in a grid, leaving a margin of 1 around
+    // them so that the steam can surround them in part 2
+    let (min, max) = Voxel::bounding_box(cubes.iter().copied()).unwrap();
+    let mut lava = VoxelGrid::new(min - Voxel::splat(1), max + Voxel::splat(1), false);
+    cubes.iter().for_each(|&cube| lava[cube] = true);
+
+    let sol1: usize = cubes.iter().map(|cube| visible_sides(cube, &lava)).sum();
+    let sol2 = expand_gas(&lava);
+    save_slices(&lava);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn visible_sides([x, y, z]: Coords3D, others: &CoordsSet) -> usize {
-    DELTAS.iter()
-          .map(|[dx, dy, dz]| [x + dx, y + dy, z + dz])
-          .filter(|pos| !others.contains(pos))
-          .count()
-}
-
-fn expand_gas(cubes: &CoordsSet) -> usize {
-    // First, determine the minimum and maximum coordinate for each axis
-    let mut mins = [0, 0, 0];
-    let mut maxs = [0, 0, 0];
+// Saves every horizontal slice of the droplet as a picture, from the bottom
+// up, if pictures were asked for
+fn save_slices(lava: &VoxelGrid<bool>) {
+    let Some(dir) = image::output_dir() else { return };
+    let mut frames = FrameWriter::new(dir.join("day18"), "slice").unwrap_or_else(|e| panic!("{e}"));
 
-    for i in 0..3 {
-        let (min, max) = cubes.iter()
-            .fold((i32::MAX, i32::MIN), |acc, cube| (min(acc.0, cube[i]), max(acc.1, cube[i])));
-
-        mins[i] = min - 1;
-        maxs[i] = max + 1;
+    for z in lava.min()[2]..=lava.max()[2] {
+        let slice = lava.slice(2, z).to_image(8, |is_lava| if is_lava { [255, 96, 0] } else { [16, 16, 32] });
+        frames.write(&slice).unwrap_or_else(|e| panic!("{e}"));
     }
-
-    // Next, do a breadth-first search to simulate the steam traversing the
-    // 3D space and count how many cube edges we touch along the way
-    let mut res = 0;
-    let mut to_visit = FxHashSet::default();
-    let mut visited = FxHashSet::default();
-
-    to_visit.insert(mins);
-
-    while !to_visit.is_empty() {
-        let mut new_to_visit = FxHashSet::default();
-
-        for pos in to_visit.drain() {
-            visited.insert(pos);
-            let (neighbors, touching) = get_neighbors(pos, cubes, &visited, maxs, mins);
-            res += touching;
-            new_to_visit.extend(neighbors);
-        }
-
-        to_visit = new_to_visit;
-    }
-
-    res
 }
 
-// Determines which neighbors of the current position are OK to visit,
-// and how many cube sides it touches
-fn get_neighbors(pos: Coords3D, cubes: &CoordsSet, visited: &CoordsSet, maxs: Coords3D, mins: Coords3D) -> (Vec<Coords3D>, usize) {
-    let mut neighbors_ok = Vec::with_capacity(6);
-    let mut touched = 0;
-
-    'outer: for [dx, dy, dz] in DELTAS {
-        let neighbor = [pos[0] + dx, pos[1] + dy, pos[2] + dz];
-
-        // Check that the neighbor is within bounds
-        for i in 0..3 {
-            if neighbor[i] < mins[i] || neighbor[i] > maxs[i] {
-                continue 'outer;
-            }
-        }
-
-        // Is the neighbor an existing cube? If so, we're next to an exposed side,
-        // add it to the touched count (note that, since every position is evaluated
-        // exactly once, this side will not be counted again)
-        if cubes.contains(&neighbor) {
-            touched += 1;
-        // Otherwise, we can visit this neighbor only if we haven't visited it before
-        } else if !visited.contains(&neighbor) {
-            neighbors_ok.push(neighbor);
-        }
-    }
-
-    (neighbors_ok, touched)
+fn visible_sides(cube: &Voxel, lava: &VoxelGrid<bool>) -> usize {
+    lava.neighbors6(cube).filter(|pos| !lava[*pos]).count()
 }
+
+fn expand_gas(lava: &VoxelGrid<bool>) -> usize {
+    // Simulate the steam traversing the 3D space from a corner, and count how
+    // many cube sides are next to every position it reaches. Since every
+    // position is reached exactly once, no side is counted twice.
+    lava.flood_fill(lava.min(), |is_lava| !is_lava).iter()
+        .map(|pos| lava.neighbors6(pos).filter(|n| lava[*n]).count())
+        .sum()
+}
\ No newline at end of file
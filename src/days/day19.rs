@@ -1,10 +1,10 @@
 use itertools::Itertools;
-use scanf::sscanf;
 use rayon::prelude::*;
 use crate::{Solution, SolutionPair};
 use Material::*;
 use std::cmp::max;
-use std::fs::read_to_string;
+use crate::etc::read_input;
+use crate::etc::parsing::extract_ints;
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -31,18 +31,18 @@ struct SearchState {
 }
 
 pub fn solve() -> SolutionPair {
-    let blueprints = read_to_string("input/day19.txt").unwrap()
+    let blueprints = read_input(19)
         .lines()
         .map(Blueprint::from_line)
         .collect_vec();
 
     let sol1 = blueprints.par_iter()
-        .map(|bp| (bp.id * get_blueprint_score(bp, 24)))
+        .map(|bp| bp.id * get_blueprint_score(bp, 24))
         .sum::<u32>();
 
     let sol2 = blueprints.par_iter()
         .take(3)
-        .map(|bp| (get_blueprint_score(bp, 32)))
+        .map(|bp| get_blueprint_score(bp, 32))
         .product::<u32>();
 
     (Solution::from(sol1), Solution::from(sol2))
@@ -142,7 +142,7 @@ fn get_max_materials(blueprint: &Blueprint) -> [u32; 4] {
         }
     }
 
-    return maxs;
+    maxs
 }
 
 impl SearchState {
@@ -174,13 +174,9 @@ impl SearchState {
 
 impl Blueprint {
     pub fn from_line(line: &str) -> Self {
-        let mut id = 0;
-        let mut ore_robot_ore_cost = 0;
-        let mut clay_robot_ore_cost = 0;
-        let (mut obs_robot_ore_cost, mut obs_robot_clay_cost) = (0, 0);
-        let (mut geo_robot_ore_cost, mut geo_robot_obs_cost) = (0, 0);
-
-        sscanf!(line, "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.", id, ore_robot_ore_cost, clay_robot_ore_cost, obs_robot_ore_cost, obs_robot_clay_cost, geo_robot_ore_cost, geo_robot_obs_cost).unwrap();
+        // The numbers always come in the same order: the blueprint ID, and then the costs of each robot
+        let [id, ore_robot_ore_cost, clay_robot_ore_cost, obs_robot_ore_cost, obs_robot_clay_cost, geo_robot_ore_cost, geo_robot_obs_cost] =
+            extract_ints(line).unwrap();
 
         let ore_robot = vec![(ore_robot_ore_cost, Ore)];
         let clay_robot = vec![(clay_robot_ore_cost, Ore)];
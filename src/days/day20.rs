@@ -1,13 +1,11 @@
-use std::fs::read_to_string;
 use crate::{Solution, SolutionPair};
+use crate::etc::read_input;
+use crate::etc::checked;
 
 ///////////////////////////////////////////////////////////////////////////////
 
 pub fn solve() -> SolutionPair {
-    let numbers: Vec<i64> = read_to_string("input/day20.txt").unwrap()
-        .lines()
-        .map(|x| x.parse().unwrap())
-        .collect();
+    let numbers: Vec<i64> = read_input(20).ints();
 
     let sol1 = decrypt(&numbers, 1, 1);
     let sol2 = decrypt(&numbers, 811_589_153, 10);
@@ -18,19 +16,20 @@ pub fn solve() -> SolutionPair {
 fn decrypt(numbers: &[i64], key: i64, rounds: usize) -> i64 {
     // Build the list that we will shuffle around.
     let mut decoded: Vec<(usize, i64)> = numbers.iter()
-        .map(|x| x * key)
         .enumerate()
+        .map(|(i, &x)| (i, checked::mul(x, key, || format!("day 20, applying the key to number {i}"))))
         .collect();
 
     let modulo = numbers.len() as i64 - 1;
 
-    for _ in 0..rounds {
+    for round in 1..=rounds {
         for orig_order in 0..numbers.len() {
             // Find the number's value and its current position in the array
             let (old_i, (_, value)) = decoded.iter().enumerate().find(|(_, t)| t.0 == orig_order).unwrap();
 
             // Determine the target position after moving it
-            let new_i = (old_i as i64 + value).rem_euclid(modulo) as usize;
+            let context = || format!("day 20, round {round}, moving number {orig_order}");
+            let new_i = checked::add(old_i as i64, *value, context).rem_euclid(modulo) as usize;
 
             // Rotate the elements between the old and new indices as needed. 
             // This is more efficient than just popping it and inserting it back, 
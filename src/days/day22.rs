@@ -7,11 +7,11 @@
 // so in theory it should work for cubes of any size as long as they are folded
 // in this particular way.
 
-use std::fs::read_to_string;
+use itertools::Itertools;
 use regex::Regex;
 
 use crate::{Solution, SolutionPair};
-use crate::etc::{Coords, VecMat, DOUBLE_NEWLINE};
+use crate::etc::{Coords, VecMat, read_input};
 use Action::*;
 
 ///////////////////////////// Typedefs and stuff ///////////////////////////////
@@ -44,8 +44,8 @@ struct Face {
 /////////////////////////////////// Main ///////////////////////////////////////
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day22.txt").unwrap();
-    let (cube_str, actions_str) = input.split_once(DOUBLE_NEWLINE).unwrap();
+    let input = read_input(22);
+    let (cube_str, actions_str) = input.paragraphs().collect_tuple().unwrap();
 
     let actions = parse_actions(actions_str);
     let faces = parse_faces(cube_str);
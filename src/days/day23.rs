@@ -1,16 +1,22 @@
-use std::fs::read_to_string;
 use itertools::Itertools;
-use rustc_hash::{FxHashSet, FxHashMap};
+use rustc_hash::FxHashMap;
 use crate::{Solution, SolutionPair};
-use crate::etc::Coords;
+use crate::etc::read_input;
+use crate::etc::{Coords, SparseGrid};
+use crate::etc::visualize::{self, Visualize};
 
 ////////////////////////////////////////////////////////////////////////////////
 
 type Pos = Coords<i32>;
-type PositionSet = FxHashSet<Pos>;
+type PositionSet = SparseGrid<i32>;
 type MoveSuggestions = FxHashMap<Pos, Option<Pos>>;
 type MoveCounter = FxHashMap<Pos, u32>;
 
+struct GroveView<'a> {
+    map: &'a PositionSet,
+    round: usize,
+}
+
 const NORTH: Pos = Pos::new(0, -1);
 const SOUTH: Pos = Pos::new(0, 1);
 const WEST: Pos = Pos::new(-1, 0);
@@ -20,7 +26,7 @@ const EAST: Pos = Pos::new(1, 0);
 
 pub fn solve() -> SolutionPair {
     // Your solution here...
-    let input = read_to_string("input/day23.txt").unwrap();
+    let input = read_input(23);
     let map = parse_elves_positions(&input);
 
     let sol1 = simulate_n_rounds(&map, 10);
@@ -34,20 +40,17 @@ pub fn solve() -> SolutionPair {
 fn simulate_n_rounds(map: &PositionSet, rounds: usize) -> i32 {
     let mut main_directions = [NORTH, SOUTH, WEST, EAST];
     let mut map = map.clone();
+    visualize::new_simulation();
     
-    for _ in 0..rounds {
+    for round in 1..=rounds {
         do_one_round(&mut map, &main_directions);
         main_directions.rotate_left(1);
+        visualize::show(&GroveView { map: &map, round });
     }
 
     // Find out the area of the minimum rectangle and substract the number of 
     // elves from it to get the number of empty spaces
-    let min_x = map.iter().map(|p| p.x).min().unwrap();
-    let max_x = map.iter().map(|p| p.x).max().unwrap();
-    let min_y = map.iter().map(|p| p.y).min().unwrap();
-    let max_y = map.iter().map(|p| p.y).max().unwrap();
-
-    (max_x - min_x + 1) * (max_y - min_y + 1) - map.len() as i32
+    map.area() - map.len() as i32
 }
 
 // Simulate the elves' movement until they all stop, returns the number of rounds
@@ -55,8 +58,10 @@ fn simulate_until_finish(map: &PositionSet) -> i32 {
     let mut main_directions = [NORTH, SOUTH, WEST, EAST];
     let mut map = map.clone();
     let mut rounds = 1;
+    visualize::new_simulation();
     
     while !do_one_round(&mut map, &main_directions) {
+        visualize::show(&GroveView { map: &map, round: rounds as usize });
         rounds += 1;
         main_directions.rotate_left(1);
     }
@@ -72,7 +77,7 @@ fn do_one_round(map: &mut PositionSet, main_directions: &[Pos]) -> bool {
     let mut movements = MoveSuggestions::default();
     let mut counters = MoveCounter::default();
 
-    for pos in map.iter().copied() {
+    for pos in map.positions() {
         let suggestion = get_movement_proposal(pos, map, main_directions);
         movements.insert(pos, suggestion);
 
@@ -89,32 +94,27 @@ fn do_one_round(map: &mut PositionSet, main_directions: &[Pos]) -> bool {
 
     for (old_pos, new_pos) in &ok_moves {
         map.remove(old_pos);
-        map.insert(*new_pos);
+        map.add(*new_pos);
     }
 
-    return ok_moves.is_empty();
+    ok_moves.is_empty()
 }
 
 // Determines the position to which a given elf will propose to move, if any
 fn get_movement_proposal(pos: Pos, map: &PositionSet, main_directions: &[Pos]) -> Option<Pos> {
     // If there are no other elves around this one, do not move
-    if (-1..=1).cartesian_product(-1..=1).all(|diff| diff == (0, 0) || !map.contains(&(pos + diff))) {
-        return None;
-    }
+    map.neighbors8(pos).next()?;
 
     // Otherwise, try to move in the following directions, in order
     for main_dir in main_directions {
-        // The auxiliary direction is orthogonal to the main direction of
+        // The side direction is orthogonal to the main direction of
         // movement, and we use it to check that the diagonals are also free
-        let aux_dirs = match main_dir {
-            Pos { x: 0, y: _ } => [(-1, 0), (0, 0), (1, 0)],
-            Pos { x: _, y: 0 } => [(0, -1), (0, 0), (0, 1)],
-            _ => unreachable!(),
-        };
+        let ahead = pos + main_dir;
+        let side = main_dir.turn_right();
 
         // The way to move in this direction is free, we can stop looking
-        if aux_dirs.iter().all(|aux| !map.contains(&(pos + main_dir + aux))) {
-            return Some(pos + main_dir);
+        if [ahead, ahead + side, ahead - side].iter().all(|p| !map.contains(p)) {
+            return Some(ahead);
         }
     }
 
@@ -126,9 +126,21 @@ fn parse_elves_positions(input: &str) -> PositionSet {
 
     for (y, line) in input.lines().enumerate() {
         for x in line.chars().enumerate().positions(|(_, ch)| ch == '#') {
-            positions.insert(Pos::new(x as i32, y as i32));
+            positions.add(Pos::new(x as i32, y as i32));
         }
     }
 
     positions
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl Visualize for GroveView<'_> {
+    fn frame(&self) -> String {
+        self.map.render(|elf| if elf.is_some() { '#' } else { '.' })
+    }
+
+    fn caption(&self) -> String {
+        format!("Round {}, the elves cover a {}x{} rectangle", self.round, self.map.width(), self.map.height())
+    }
+}
@@ -1,8 +1,9 @@
-use std::fs::read_to_string;
 use itertools::Itertools;
-use pathfinding::prelude::astar;
 use crate::{Solution, SolutionPair};
+use crate::etc::read_input;
 use crate::etc::Coords;
+use crate::etc::search::astar;
+use crate::etc::visualize::{self, Visualize};
 
 ////////////////////////////////////////////////////////////////////////////////
 
@@ -40,10 +41,15 @@ struct SearchState {
     minute: i32
 }
 
+struct ExpeditionView<'a> {
+    field: &'a FieldInfo,
+    state: SearchState,
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day24.txt").unwrap();
+    let input = read_input(24);
     let field_info = parse(&input);
 
     let start = field_info.start;
@@ -62,13 +68,21 @@ pub fn solve() -> SolutionPair {
 // a goal point in the field, starting at a given minute
 fn search_best_path(field: &FieldInfo, start: Pos, goal: Pos, initial_min: i32) -> i32 {
     let initial = SearchState { position: start, minute: initial_min };
-    let path = astar(&initial,
-         |state| get_neighbors(state, field),  // Navigation function
+    let result = astar(initial,
+         |state| get_neighbors(&state, field),  // Navigation function
          |state| state.position.manhattan_dist(&goal),  // Heuristic
          |state| state.position == goal  // Success condition
     );
 
-    path.unwrap().1
+    // The search explores many states at once, so only replay the best path found
+    visualize::new_simulation();
+    if visualize::is_enabled() {
+        for state in result.path().unwrap() {
+            visualize::show(&ExpeditionView { field, state });
+        }
+    }
+
+    result.cost().unwrap()
 }
 
 // Calculates the possible actions for a search state
@@ -153,3 +167,45 @@ fn parse(input: &str) -> FieldInfo {
 
     FieldInfo { width, height, start, goal, blz_cols, blz_rows }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl Visualize for ExpeditionView<'_> {
+    // Draws the valley like in the puzzle input, with the expedition as E
+    // and the amount of blizzards where more than one overlap
+    fn frame(&self) -> String {
+        let field = self.field;
+
+        (-1..=field.height).map(|y| {
+            (-1..=field.width).map(|x| {
+                let pos = Pos::new(x, y);
+                if pos == self.state.position {
+                    return 'E';
+                } else if pos == field.start || pos == field.goal {
+                    return '.';
+                } else if x < 0 || x >= field.width || y < 0 || y >= field.height {
+                    return '#';
+                }
+
+                let blizzards = field.blz_cols[x as usize].iter().chain(&field.blz_rows[y as usize])
+                    .filter(|blz| blizzard_position_at(blz, self.state.minute) == pos)
+                    .collect_vec();
+
+                match blizzards.as_slice() {
+                    [] => '.',
+                    [blz] => match blz.direction {
+                        UP => '^',
+                        DOWN => 'v',
+                        LEFT => '<',
+                        _ => '>',
+                    },
+                    many => char::from_digit(many.len().min(9) as u32, 10).unwrap(),
+                }
+            }).collect::<String>()
+        }).collect_vec().join("\n")
+    }
+
+    fn caption(&self) -> String {
+        format!("Minute {}", self.state.minute)
+    }
+}
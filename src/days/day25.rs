@@ -1,12 +1,12 @@
 use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
+use crate::etc::read_input;
 
 ///////////////////////////////////////////////////////////////////////////////
 
 const SNAFU_CHARS: [char; 5] = ['0', '1', '2', '=', '-'];
 
 pub fn solve() -> SolutionPair {
-    let input = read_to_string("input/day25.txt").unwrap();
+    let input = read_input(25);
     let code = input.lines().map(snafu2dec).sum();
 
     let sol1 = dec2snafu(code);
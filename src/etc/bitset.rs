@@ -0,0 +1,162 @@
+// Day 3 only needs to insert and intersect, the rest of the operations
+// are kept so that BitSet can be used as a general set
+#![allow(dead_code)]
+
+/** A set of small non-negative integers, stored as one bit per possible value,
+    which grows as needed to fit the largest value inserted. The last word is
+    never zero, so that equal sets are stored the same way. */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self { words: Vec::new() }
+    }
+
+    // Returns whether the value was not in the set already
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, value % 64);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        let was_absent = self.words[word] & (1 << bit) == 0;
+        self.words[word] |= 1 << bit;
+        was_absent
+    }
+
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, value % 64);
+        let was_present = self.contains(value);
+        if was_present {
+            self.words[word] &= !(1 << bit);
+            self.trim();
+        }
+        was_present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words.get(value / 64).is_some_and(|w| w & (1 << (value % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    // Iterates over the values in the set, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        self.words.iter_mut().zip(&other.words).for_each(|(a, b)| *a &= b);
+        self.trim();
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        self.words.iter_mut().zip(&other.words).for_each(|(a, b)| *a |= b);
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| { self.insert(value); });
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(values: &[usize]) -> BitSet {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn grows_to_fit_large_values() {
+        let mut set = BitSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(set.insert(200));
+        assert!(!set.insert(3));
+        assert_eq!((set.len(), set.iter().collect::<Vec<_>>()), (2, vec![3, 200]));
+        assert!(set.contains(200) && !set.contains(199) && !set.contains(1000));
+
+        assert!(set.remove(200));
+        assert!(!set.remove(200) && !set.remove(1000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    fn set_operations_between_different_sizes() {
+        let (small, large) = (set(&[1, 2, 3]), set(&[2, 3, 130]));
+
+        assert_eq!(small.intersection(&large).iter().collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(large.intersection(&small).iter().collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(small.union(&large).iter().collect::<Vec<_>>(), [1, 2, 3, 130]);
+        assert_eq!(large.union(&small), small.union(&large));
+        assert!(small.intersection(&BitSet::new()).is_empty());
+    }
+
+    #[test]
+    fn sets_with_the_same_values_are_equal() {
+        let mut shrunk = set(&[3, 200]);
+        shrunk.remove(200);
+        assert_eq!(shrunk, set(&[3]));
+
+        shrunk.remove(3);
+        assert_eq!(shrunk, BitSet::new());
+
+        assert_eq!(set(&[3, 70]).intersection(&set(&[3, 140])), set(&[3]));
+        assert_eq!(set(&[70]).intersection(&set(&[140])), BitSet::new());
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt::Display;
+use num_traits::int::PrimInt;
+
+// Arithmetic for values that could grow large enough to overflow. With the
+// checked-math feature enabled, an overflow panics and reports the operands
+// and where it happened (as described by the context function), instead of
+// silently wrapping around. Without it, these are the regular operators.
+
+pub fn add<T: PrimInt + Display, F: FnOnce() -> String>(a: T, b: T, context: F) -> T {
+    if cfg!(feature = "checked-math") {
+        a.checked_add(&b).unwrap_or_else(|| overflow(a, "+", b, context))
+    } else {
+        a + b
+    }
+}
+
+pub fn mul<T: PrimInt + Display, F: FnOnce() -> String>(a: T, b: T, context: F) -> T {
+    if cfg!(feature = "checked-math") {
+        a.checked_mul(&b).unwrap_or_else(|| overflow(a, "*", b, context))
+    } else {
+        a * b
+    }
+}
+
+fn overflow<T: Display, F: FnOnce() -> String>(a: T, op: &str, b: T, context: F) -> ! {
+    panic!("Arithmetic overflow in {}: {a} {op} {b}", context())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> String {
+        "ctx".to_owned()
+    }
+
+    #[test]
+    fn results_without_overflow() {
+        assert_eq!(add(100_i8, 27, ctx), 127);
+        assert_eq!(mul(-16_i8, 8, ctx), -128);
+    }
+
+    #[test]
+    #[cfg(feature = "checked-math")]
+    #[should_panic(expected = "Arithmetic overflow in ctx: 100 + 28")]
+    fn checked_add_reports_operands_and_context() {
+        add(100_i8, 28, ctx);
+    }
+
+    #[test]
+    #[cfg(feature = "checked-math")]
+    #[should_panic(expected = "Arithmetic overflow in ctx: -16 * 9")]
+    fn checked_mul_reports_operands_and_context() {
+        mul(-16_i8, 9, ctx);
+    }
+
+    // Without the feature these are the plain operators, which
+    // only catch the overflow in debug builds, without context
+    #[test]
+    #[cfg(all(not(feature = "checked-math"), debug_assertions))]
+    #[should_panic(expected = "attempt to add with overflow")]
+    fn plain_add_without_the_feature() {
+        add(100_i8, 28, ctx);
+    }
+}
@@ -1,7 +1,13 @@
-use std::ops::{Add, Sub, AddAssign};
+// Not every rotation, neighbour iterator and conversion is used by a solver,
+// they are kept so that Coords offers the whole set
+#![allow(dead_code)]
+
+use std::ops::{Add, Sub, AddAssign, Neg, Mul};
 use num_traits::int::PrimInt;
 use num_traits::sign::Signed;
 
+use super::utils::Pos2D;
+
 /** A pair of signed integers representing 2D coordinates */
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Coords<T: PrimInt + Signed> {
@@ -9,10 +15,14 @@ pub struct Coords<T: PrimInt + Signed> {
     pub y: T,
 }
 
+// Walks the cells of a straight line using Bresenham's algorithm, which
+// also covers the horizontal, vertical and exact diagonal cases
 pub struct SegmentIter<T: PrimInt + Signed> {
-    delta: Coords<T>,
-    current: Coords<T>,
+    current: Option<Coords<T>>,
     end: Coords<T>,
+    step: Coords<T>,
+    dist: Coords<T>,
+    error: T,
 }
 
 impl<T: PrimInt + Signed> Coords<T> {
@@ -20,32 +30,102 @@ impl<T: PrimInt + Signed> Coords<T> {
         Self { x, y }
     }
 
-    // Iterates from this coordinate to the target coordinate, both inclusive
-    // Only works for coordinates that share at least one coordinate
+    // Iterates from this coordinate to the target coordinate, both inclusive,
+    // in a straight line. Iterating to the same coordinate yields only itself.
     pub fn iter_to(&self, other: &Self) -> SegmentIter<T> {
-        assert!(self.x == other.x || self.y == other.y, "At least one coordinate must be shared");
-        let dx = (other.x - self.x).signum();
-        let dy = (other.y - self.y).signum();
-        let delta = Self { x: dx, y: dy };
-        SegmentIter { delta: Self { x: dx, y: dy }, current: *self - delta, end: *other }
+        let step = Self::new((other.x - self.x).signum(), (other.y - self.y).signum());
+        let dist = Self::new((other.x - self.x).abs(), -(other.y - self.y).abs());
+        SegmentIter { current: Some(*self), end: *other, step, dist, error: dist.x + dist.y }
+    }
+
+    // Same as iter_to, but a zero-length segment yields no coordinates at all
+    pub fn segment_to(&self, other: &Self) -> SegmentIter<T> {
+        let mut iter = self.iter_to(other);
+        if self == other {
+            iter.current = None;
+        }
+        iter
     }
 
     pub fn manhattan_dist(&self, other: &Self) -> T {
         (self.x - other.x).abs() + (self.y - other.y).abs()
     }
+
+    // Number of king moves between both coordinates
+    pub fn chebyshev_dist(&self, other: &Self) -> T {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    // Rotations by 90 degrees around the origin. The Y axis grows downwards,
+    // so clockwise means going from right to down, down to left and so on.
+    pub fn rotate_cw(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    // For coordinates used as a heading, the direction after turning 90 degrees
+    pub fn turn_right(&self) -> Self {
+        self.rotate_cw()
+    }
+
+    pub fn turn_left(&self) -> Self {
+        self.rotate_ccw()
+    }
+
+    // The 4 orthogonally adjacent coordinates, clockwise starting from the one above
+    pub fn neighbors4(&self) -> impl Iterator<Item = Self> {
+        let (o, z) = (T::one(), T::zero());
+        let center = *self;
+        [(z, -o), (o, z), (z, o), (-o, z)].into_iter().map(move |delta| center + delta)
+    }
+
+    // The 8 adjacent coordinates including diagonals, clockwise starting from the top left one
+    pub fn neighbors8(&self) -> impl Iterator<Item = Self> {
+        let (o, z) = (T::one(), T::zero());
+        let center = *self;
+        [(-o, -o), (z, -o), (o, -o), (o, z), (o, o), (z, o), (-o, o), (-o, z)].into_iter().map(move |delta| center + delta)
+    }
+
+    pub fn from_pos2d((x, y): Pos2D) -> Self {
+        Self::new(
+            T::from(x).unwrap_or_else(|| panic!("X coordinate out of range: {x}")),
+            T::from(y).unwrap_or_else(|| panic!("Y coordinate out of range: {y}"))
+        )
+    }
+
+    // Returns None if any of the coordinates is negative
+    pub fn to_pos2d(self) -> Option<Pos2D> {
+        Some((self.x.to_usize()?, self.y.to_usize()?))
+    }
 }
 
 impl<T: PrimInt + Signed> Iterator for SegmentIter<T> {
     type Item = Coords<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.current {
-            x if x == self.end => None,
-            _ => {
-                self.current += self.delta;
-                Some(self.current)
-            },
-        }
+        let current = self.current?;
+
+        self.current = if current == self.end {
+            None
+        } else {
+            // Advance in whichever axes keep us closest to the ideal line
+            let mut next = current;
+            let double_error = self.error + self.error;
+            if double_error >= self.dist.y {
+                self.error = self.error + self.dist.y;
+                next.x = next.x + self.step.x;
+            }
+            if double_error <= self.dist.x {
+                self.error = self.error + self.dist.x;
+                next.y = next.y + self.step.y;
+            }
+            Some(next)
+        };
+
+        Some(current)
     }
 }
 
@@ -77,6 +157,22 @@ impl <T: PrimInt + Signed> Sub<Coords<T>> for Coords<T> {
     }
 }
 
+impl <T: PrimInt + Signed> Neg for Coords<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl <T: PrimInt + Signed> Mul<T> for Coords<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
 impl <T: PrimInt + Signed> Add<&Coords<T>> for Coords<T> {
     type Output = Self;
 
@@ -109,3 +205,58 @@ impl <T: PrimInt + Signed> Add<&(T, T)> for Coords<T> {
         Self::new(self.x + rhs.0, self.y + rhs.1)
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Pos = Coords<i32>;
+
+    fn segment(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        Pos::from(from).iter_to(&Pos::from(to)).map(|p| (p.x, p.y)).collect()
+    }
+
+    #[test]
+    fn segments_along_axes_and_diagonals() {
+        assert_eq!(segment((2, 5), (2, 3)), [(2, 5), (2, 4), (2, 3)]);
+        assert_eq!(segment((0, 0), (3, -3)), [(0, 0), (1, -1), (2, -2), (3, -3)]);
+        assert_eq!(segment((1, 1), (1, 1)), [(1, 1)]);
+        assert_eq!(Pos::new(1, 1).segment_to(&Pos::new(1, 1)).count(), 0);
+        assert_eq!(Pos::new(1, 1).segment_to(&Pos::new(1, 2)).count(), 2);
+    }
+
+    #[test]
+    fn segments_along_general_lines() {
+        // Every step moves one cell along the longer axis, and
+        // sometimes one along the shorter one too
+        assert_eq!(segment((0, 0), (5, 2)), [(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 2)]);
+        assert_eq!(segment((0, 0), (1, 4)), [(0, 0), (0, 1), (1, 2), (1, 3), (1, 4)]);
+
+        // Going backwards also includes both endpoints
+        assert_eq!(segment((5, 2), (0, 0)), [(5, 2), (4, 2), (3, 1), (2, 1), (1, 0), (0, 0)]);
+        assert_eq!(segment((3, -1), (-4, 2)), [(3, -1), (2, -1), (1, 0), (0, 0), (-1, 1), (-2, 1), (-3, 2), (-4, 2)]);
+    }
+
+    #[test]
+    fn turns_and_rotations_undo_each_other() {
+        let up = Pos::new(0, -1);
+        assert_eq!(up.turn_right(), Pos::new(1, 0));
+        assert_eq!(up.turn_left(), Pos::new(-1, 0));
+        assert_eq!(up.turn_right().turn_right().turn_right().turn_right(), up);
+        assert_eq!(Pos::new(3, 7).rotate_cw().rotate_ccw(), Pos::new(3, 7));
+
+        // The first neighbor of the origin is up, and the rest are its turns to the right
+        let around = Pos::new(0, 0).neighbors4().collect::<Vec<_>>();
+        assert_eq!(around, [up, up.turn_right(), -up, up.turn_left()]);
+        assert_eq!(Pos::new(4, 2).manhattan_dist(&Pos::new(1, 6)), 7);
+        assert_eq!(Pos::new(4, 2).chebyshev_dist(&Pos::new(1, 6)), 4);
+    }
+
+    #[test]
+    fn conversion_to_indices_rejects_negatives() {
+        assert_eq!(Pos::from_pos2d((3, 4)).to_pos2d(), Some((3, 4)));
+        assert_eq!(Pos::new(3, -1).to_pos2d(), None);
+    }
+}
@@ -0,0 +1,156 @@
+// The distance metrics are kept for completeness, day 18 only needs neighbours
+#![allow(dead_code)]
+
+use std::ops::{Add, Sub, AddAssign, Neg, Mul, Index, IndexMut};
+use num_traits::int::PrimInt;
+use num_traits::sign::Signed;
+
+use super::coords::Coords;
+
+/** N signed integers representing coordinates in an N-dimensional space */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CoordsN<T: PrimInt + Signed, const N: usize> {
+    pub axes: [T; N],
+}
+
+impl<T: PrimInt + Signed, const N: usize> CoordsN<T, N> {
+    pub const fn new(axes: [T; N]) -> Self {
+        Self { axes }
+    }
+
+    // The same value in every axis
+    pub fn splat(value: T) -> Self {
+        Self::new([value; N])
+    }
+
+    pub fn manhattan_dist(&self, other: &Self) -> T {
+        self.zip_with(other, |a, b| (a - b).abs()).axes.into_iter().fold(T::zero(), |acc, x| acc + x)
+    }
+
+    pub fn chebyshev_dist(&self, other: &Self) -> T {
+        self.zip_with(other, |a, b| (a - b).abs()).axes.into_iter().fold(T::zero(), T::max)
+    }
+
+    // The 2*N coordinates that are adjacent to this one along a single axis
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> {
+        let center = *self;
+        (0..N).flat_map(move |axis| {
+            [T::one(), -T::one()].into_iter().map(move |delta| {
+                let mut neighbor = center;
+                neighbor[axis] = neighbor[axis] + delta;
+                neighbor
+            })
+        })
+    }
+
+    // Smallest and largest value in every axis among a set of coordinates,
+    // or None if there are no coordinates at all
+    pub fn bounding_box<I>(coords: I) -> Option<(Self, Self)>
+    where I: IntoIterator<Item = Self> {
+        coords.into_iter().fold(None, |bounds, pos| match bounds {
+            None => Some((pos, pos)),
+            Some((min, max)) => Some((min.zip_with(&pos, T::min), max.zip_with(&pos, T::max))),
+        })
+    }
+
+    // Checks if this coordinate is inside a box, both corners inclusive
+    pub fn is_within(&self, min: &Self, max: &Self) -> bool {
+        (0..N).all(|i| self[i] >= min[i] && self[i] <= max[i])
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn zip_with<F>(&self, other: &Self, func: F) -> Self
+    where F: Fn(T, T) -> T {
+        Self::new(std::array::from_fn(|i| func(self[i], other[i])))
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> From<[T; N]> for CoordsN<T, N> {
+    fn from(axes: [T; N]) -> Self {
+        Self::new(axes)
+    }
+}
+
+impl<T: PrimInt + Signed> From<Coords<T>> for CoordsN<T, 2> {
+    fn from(Coords { x, y }: Coords<T>) -> Self {
+        Self::new([x, y])
+    }
+}
+
+impl<T: PrimInt + Signed> From<CoordsN<T, 2>> for Coords<T> {
+    fn from(CoordsN { axes: [x, y] }: CoordsN<T, 2>) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> Index<usize> for CoordsN<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.axes[axis]
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> IndexMut<usize> for CoordsN<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.axes[axis]
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> Add<CoordsN<T, N>> for CoordsN<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a + b)
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> AddAssign<CoordsN<T, N>> for CoordsN<T, N> {
+    fn add_assign(&mut self, rhs: CoordsN<T, N>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> Sub<CoordsN<T, N>> for CoordsN<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a - b)
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> Neg for CoordsN<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(self.axes.map(|a| -a))
+    }
+}
+
+impl<T: PrimInt + Signed, const N: usize> Mul<T> for CoordsN<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.axes.map(|a| a * rhs))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_and_bounds() {
+        let (a, b) = (CoordsN::new([1, -2, 3]), CoordsN::new([4, 2, 3]));
+        assert_eq!((a.manhattan_dist(&b), a.chebyshev_dist(&b)), (7, 4));
+        assert_eq!(a.neighbors().count(), 6);
+        assert!(a.neighbors().all(|n| n.manhattan_dist(&a) == 1));
+
+        assert_eq!(CoordsN::bounding_box([a, b]), Some((a, b)));
+        assert_eq!(CoordsN::<i32, 3>::bounding_box([]), None);
+        assert!(CoordsN::new([2, 0, 3]).is_within(&a, &b) && !CoordsN::new([2, 0, 4]).is_within(&a, &b));
+    }
+}
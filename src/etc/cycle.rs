@@ -0,0 +1,236 @@
+// Day 17 only needs find_by_hash and extrapolate, Floyd's and Brent's methods
+// are kept for sequences whose states are too large to remember them all
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::hash::Hash;
+
+use num_traits::int::PrimInt;
+use rustc_hash::FxHashMap;
+
+use super::checked;
+
+/** A cycle found in a sequence of states: after the first `start` states,
+    the sequence repeats itself every `period` states. It also keeps the
+    metric of every state up to the first repetition, to extrapolate it. */
+#[derive(Clone, Debug)]
+pub struct Cycle<M> {
+    pub start: usize,
+    pub period: usize,
+    metrics: Vec<M>,
+}
+
+impl<M> Cycle<M> {
+    // The metric of the n-th state, for metrics that only depend on the state
+    // itself, and so repeat along with it
+    pub fn nth(&self, n: usize) -> &M {
+        &self.metrics[self.equivalent_index(n)]
+    }
+
+    // The metrics of every state until the cycle starts repeating
+    pub fn metrics(&self) -> &[M] {
+        &self.metrics
+    }
+
+    // The earliest state that's known to be the same as the n-th one
+    fn equivalent_index(&self, n: usize) -> usize {
+        if n < self.start { n } else { self.start + (n - self.start) % self.period }
+    }
+}
+
+impl<M: PrimInt + Display> Cycle<M> {
+    // The metric of the n-th state, for metrics that grow by the same
+    // amount every time the cycle repeats, such as a running total.
+    // The context tells where an overflow happened, as in checked.
+    pub fn extrapolate<F: Fn() -> String>(&self, n: usize, context: F) -> M {
+        if n < self.metrics.len() {
+            return self.metrics[n];
+        }
+
+        let context = || format!("{}, extrapolating the state #{n} of a cycle of length {}", context(), self.period);
+        let growth = self.metrics[self.start + self.period] - self.metrics[self.start];
+        let reps = M::from((n - self.start) / self.period)
+            .unwrap_or_else(|| panic!("Too many repetitions in {}", context()));
+
+        checked::add(self.metrics[self.equivalent_index(n)], checked::mul(growth, reps, context), context)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Finds the first repeated state in a sequence of (state, metric) pairs, remembering
+// every state seen so far. The sequence doesn't need to be generated by a function
+// of the previous state, so the state can be a summary of a larger simulation.
+pub fn find_by_hash<S, M, I>(sequence: I) -> Option<Cycle<M>>
+where S: Hash + Eq, I: IntoIterator<Item = (S, M)> {
+    let mut seen: FxHashMap<S, usize> = FxHashMap::default();
+    let mut metrics = vec![];
+
+    for (i, (state, metric)) in sequence.into_iter().enumerate() {
+        metrics.push(metric);
+        if let Some(&start) = seen.get(&state) {
+            return Some(Cycle { start, period: i - start, metrics });
+        }
+        seen.insert(state, i);
+    }
+
+    None
+}
+
+// Floyd's tortoise and hare: finds the cycle using constant memory, by moving
+// one pointer twice as fast as the other. Never ends if there is no cycle.
+pub fn floyd<S, M, F, G>(initial: S, step: F, metric: G) -> Cycle<M>
+where S: Eq + Clone, F: Fn(&S) -> S, G: Fn(&S) -> M {
+    // Find some state inside the cycle
+    let mut slow = step(&initial);
+    let mut fast = step(&slow);
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&step(&fast));
+    }
+
+    // The distance between both is now a multiple of the period, so
+    // they meet again right where the cycle starts
+    let mut start = 0;
+    slow = initial.clone();
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        start += 1;
+    }
+
+    let mut period = 1;
+    fast = step(&slow);
+    while slow != fast {
+        fast = step(&fast);
+        period += 1;
+    }
+
+    with_metrics(initial, start, period, step, metric)
+}
+
+// Brent's algorithm: also uses constant memory, but usually needs fewer steps
+// than Floyd's, since it finds the period first. Never ends if there is no cycle.
+pub fn brent<S, M, F, G>(initial: S, step: F, metric: G) -> Cycle<M>
+where S: Eq + Clone, F: Fn(&S) -> S, G: Fn(&S) -> M {
+    // Look for the period in windows that double in size every time
+    let mut power = 1;
+    let mut period = 1;
+    let mut slow = initial.clone();
+    let mut fast = step(&initial);
+    while slow != fast {
+        if power == period {
+            slow = fast.clone();
+            power *= 2;
+            period = 0;
+        }
+        fast = step(&fast);
+        period += 1;
+    }
+
+    // Keep two states a period apart, they first meet where the cycle starts
+    let mut start = 0;
+    slow = initial.clone();
+    fast = (0..period).fold(initial.clone(), |state, _| step(&state));
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        start += 1;
+    }
+
+    with_metrics(initial, start, period, step, metric)
+}
+
+// Replays the sequence up to the first repetition to record the metrics
+fn with_metrics<S, M, F, G>(initial: S, start: usize, period: usize, step: F, metric: G) -> Cycle<M>
+where F: Fn(&S) -> S, G: Fn(&S) -> M {
+    let mut metrics = Vec::with_capacity(start + period + 1);
+    let mut state = initial;
+    metrics.push(metric(&state));
+
+    for _ in 0..start + period {
+        state = step(&state);
+        metrics.push(metric(&state));
+    }
+
+    Cycle { start, period, metrics }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2... 10, and then 4, 5... 10 over and over
+    fn step(x: &u64) -> u64 {
+        if *x < 10 { x + 1 } else { 4 }
+    }
+
+    fn sequence() -> impl Iterator<Item = u64> {
+        std::iter::successors(Some(0), |x| Some(step(x)))
+    }
+
+    fn ctx() -> String {
+        "ctx".to_owned()
+    }
+
+    #[test]
+    fn every_method_finds_the_same_cycle() {
+        let by_hash = find_by_hash(sequence().map(|x| (x, x * x))).unwrap();
+        let by_floyd = floyd(0, step, |x| x * x);
+        let by_brent = brent(0, step, |x| x * x);
+
+        for cycle in [&by_hash, &by_floyd, &by_brent] {
+            assert_eq!((cycle.start, cycle.period), (4, 7));
+            assert_eq!(cycle.metrics()[..12], sequence().map(|x| x * x).take(12).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn cycles_that_start_right_away() {
+        let rotate = |x: &u64| (x + 1) % 5;
+        for cycle in [floyd(3, rotate, |&x| x), brent(3, rotate, |&x| x)] {
+            assert_eq!((cycle.start, cycle.period), (0, 5));
+            assert_eq!(*cycle.nth(1_000_001), 4);
+        }
+
+        let cycle = floyd(7_u64, |&x| x, |&x| x);
+        assert_eq!((cycle.start, cycle.period), (0, 1));
+    }
+
+    #[test]
+    fn repeats_and_extrapolates_metrics() {
+        let cycle = brent(0, step, |&x| x);
+        let expected = sequence().take(1000).collect::<Vec<_>>();
+        for (n, x) in expected.iter().enumerate() {
+            assert_eq!(cycle.nth(n), x, "state #{n}");
+        }
+
+        // The sum of the states before each one grows by 4 + 5 + ... + 10 every cycle
+        let with_totals = || sequence().scan(0, |total, x| {
+            let before = *total;
+            *total += x;
+            Some((x, before))
+        });
+        let cycle = find_by_hash(with_totals()).unwrap();
+        for (n, (_, total)) in with_totals().take(1000).enumerate() {
+            assert_eq!(cycle.extrapolate(n, ctx), total, "state #{n}");
+        }
+        assert_eq!(cycle.extrapolate(4 + 7 * 1_000_000, ctx), 6 + 49 * 1_000_000);
+    }
+
+    #[test]
+    #[cfg(feature = "checked-math")]
+    #[should_panic(expected = "Arithmetic overflow in ctx, extrapolating the state #4 of a cycle of length 1: 100 * 3")]
+    fn overflows_tell_the_caller_context() {
+        // 0, 100, 200... as u8
+        let cycle = find_by_hash((0_u8..).map(|x| (x.min(1), x * 100))).unwrap();
+        cycle.extrapolate(4, ctx);
+    }
+
+    #[test]
+    fn finite_sequences_without_repetitions() {
+        assert!(find_by_hash((0..100).map(|x| (x, x))).is_none());
+    }
+}
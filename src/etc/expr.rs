@@ -0,0 +1,198 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Sub, Mul, Div};
+
+use super::rational::Rational;
+
+/** A symbolic arithmetic expression over integer constants and named variables */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Const(i64),
+    Var(String),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+}
+
+/** An expression simplified to the form `coef * var + constant` */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Linear {
+    pub var: Option<String>,
+    pub coef: Rational,
+    pub constant: Rational,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprError {
+    NonLinear,
+    DivisionByZero,
+    MultipleVariables(String, String),
+    NoSolution,
+    InfiniteSolutions,
+    Overflow,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl Expr {
+    pub fn var(name: &str) -> Self {
+        Self::Var(name.to_owned())
+    }
+
+    // Simplifies the expression into a linear function of its only variable.
+    // Fails if the variable is multiplied by itself or appears in a divisor.
+    pub fn linearize(&self) -> Result<Linear, ExprError> {
+        match self {
+            Self::Const(x) => Ok(Linear::constant(Rational::from(i128::from(*x)))),
+            Self::Var(name) => Ok(Linear { var: Some(name.clone()), coef: Rational::from(1), constant: Rational::from(0) }),
+            Self::Add(left, right) => left.linearize()?.add(&right.linearize()?),
+            Self::Sub(left, right) => left.linearize()?.add(&right.linearize()?.scale(Rational::from(-1))?),
+            Self::Mul(left, right) => {
+                let (left, right) = (left.linearize()?, right.linearize()?);
+                match (left.as_constant(), right.as_constant()) {
+                    (Some(k), _) => right.scale(k),
+                    (_, Some(k)) => left.scale(k),
+                    _ => Err(ExprError::NonLinear),
+                }
+            },
+            Self::Div(left, right) => {
+                let divisor = right.linearize()?.as_constant().ok_or(ExprError::NonLinear)?;
+                if divisor.is_zero() {
+                    return Err(ExprError::DivisionByZero);
+                }
+                left.linearize()?.scale(divisor.checked_recip().unwrap())
+            },
+        }
+    }
+
+    // Computes the value of an expression without variables
+    pub fn evaluate(&self) -> Result<Rational, ExprError> {
+        let linear = self.linearize()?;
+        linear.as_constant().ok_or(ExprError::NonLinear)
+    }
+}
+
+// Finds the value of the variable that makes both expressions equal
+pub fn solve(left: &Expr, right: &Expr) -> Result<Rational, ExprError> {
+    let diff = left.linearize()?.add(&right.linearize()?.scale(Rational::from(-1))?)?;
+
+    // coef * x + constant = 0  =>  x = -constant / coef
+    match (diff.coef.is_zero(), diff.constant.is_zero()) {
+        (true, true) => Err(ExprError::InfiniteSolutions),
+        (true, false) => Err(ExprError::NoSolution),
+        _ => diff.constant.checked_div(diff.coef).and_then(Rational::checked_neg).ok_or(ExprError::Overflow),
+    }
+}
+
+impl Linear {
+    pub fn constant(value: Rational) -> Self {
+        Self { var: None, coef: Rational::from(0), constant: value }
+    }
+
+    pub fn as_constant(&self) -> Option<Rational> {
+        self.coef.is_zero().then_some(self.constant)
+    }
+
+    fn add(&self, other: &Self) -> Result<Self, ExprError> {
+        let var = match (&self.var, &other.var) {
+            (Some(a), Some(b)) if a != b => return Err(ExprError::MultipleVariables(a.clone(), b.clone())),
+            (a, b) => a.clone().or_else(|| b.clone()),
+        };
+        let coef = self.coef.checked_add(other.coef).ok_or(ExprError::Overflow)?;
+        let constant = self.constant.checked_add(other.constant).ok_or(ExprError::Overflow)?;
+        Ok(Self::new(var, coef, constant))
+    }
+
+    fn scale(&self, k: Rational) -> Result<Self, ExprError> {
+        let coef = self.coef.checked_mul(k).ok_or(ExprError::Overflow)?;
+        let constant = self.constant.checked_mul(k).ok_or(ExprError::Overflow)?;
+        Ok(Self::new(self.var.clone(), coef, constant))
+    }
+
+    // A variable that cancelled out is gone, so that it doesn't
+    // clash with a different one that appears later
+    fn new(var: Option<String>, coef: Rational, constant: Rational) -> Self {
+        Self { var: var.filter(|_| !coef.is_zero()), coef, constant }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! impl_expr_op {
+    ($trait_:ident, $func_:ident, $kind_:ident) => {
+        impl $trait_ for Expr {
+            type Output = Expr;
+
+            fn $func_(self, rhs: Self) -> Self::Output {
+                Expr::$kind_(Box::new(self), Box::new(rhs))
+            }
+        }
+    }
+}
+
+impl_expr_op!(Add, add, Add);
+impl_expr_op!(Sub, sub, Sub);
+impl_expr_op!(Mul, mul, Mul);
+impl_expr_op!(Div, div, Div);
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonLinear => write!(f, "the expression is not linear"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::MultipleVariables(a, b) => write!(f, "more than one variable: {a} and {b}"),
+            Self::NoSolution => write!(f, "the equation has no solution"),
+            Self::InfiniteSolutions => write!(f, "the equation has infinite solutions"),
+            Self::Overflow => write!(f, "an intermediate value is too large"),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(x: i64) -> Expr {
+        Expr::Const(x)
+    }
+
+    fn frac(num: i128, den: i128) -> Rational {
+        Rational::checked_new(num, den).unwrap()
+    }
+
+    #[test]
+    fn linearizes_and_evaluates() {
+        let expr = (Expr::var("x") * num(2) + num(3)) / num(4);
+        let expected = Linear { var: Some("x".to_string()), coef: frac(1, 2), constant: frac(3, 4) };
+        assert_eq!(expr.linearize(), Ok(expected));
+
+        assert_eq!(((num(7) - num(3)) * num(5) / num(3)).evaluate(), Ok(frac(20, 3)));
+        assert_eq!((num(2) - Expr::var("x")).evaluate(), Err(ExprError::NonLinear));
+    }
+
+    #[test]
+    fn solves_for_the_only_variable() {
+        // 4 * (x - 3) / 6 = x / 2 + 1
+        let left = num(4) * (Expr::var("x") - num(3)) / num(6);
+        let right = Expr::var("x") / num(2) + num(1);
+        assert_eq!(solve(&left, &right), Ok(Rational::from(18)));
+
+        // Variables multiplied by zero disappear
+        assert_eq!(solve(&(Expr::var("x") * num(0) + Expr::var("y")), &num(1)), Ok(Rational::from(1)));
+        assert_eq!(solve(&(Expr::var("x") - Expr::var("x") + Expr::var("y")), &num(5)), Ok(Rational::from(5)));
+    }
+
+    #[test]
+    fn reports_unsolvable_equations() {
+        let x = || Expr::var("x");
+        assert_eq!(solve(&(x() * x()), &num(4)), Err(ExprError::NonLinear));
+        assert_eq!(solve(&(num(1) / x()), &num(4)), Err(ExprError::NonLinear));
+        assert_eq!(solve(&(x() / (x() - x())), &num(4)), Err(ExprError::DivisionByZero));
+        assert_eq!(solve(&(x() + num(1)), &(x() + num(2))), Err(ExprError::NoSolution));
+        assert_eq!(solve(&(x() * num(2)), &(x() + x())), Err(ExprError::InfiniteSolutions));
+        assert_eq!(solve(&(x() + Expr::var("y")), &num(1)), Err(ExprError::MultipleVariables("x".to_string(), "y".to_string())));
+        assert_eq!((num(i64::MAX) * num(i64::MAX) * num(i64::MAX)).evaluate(), Err(ExprError::Overflow));
+    }
+}
@@ -1,22 +1,129 @@
+// Day 16 only looks names up by index, the rest of the reverse lookup is
+// kept so that other solvers can list or count what was assigned
+#![allow(dead_code)]
+
 use rustc_hash::FxHashMap;
+use std::borrow::Borrow;
 use std::hash::Hash;
+use std::ops::Index;
 
-pub struct IDAssigner<T: Hash + Eq> {
+/** Assigns consecutive numeric IDs to elements as they are first seen,
+    and remembers which element each ID belongs to */
+pub struct IDAssigner<T: Hash + Eq + Clone> {
     data: FxHashMap<T, u32>,
+    names: Vec<T>,
+}
+
+/** A read-only IDAssigner, for once all the elements have been seen */
+pub struct Interner<T: Hash + Eq + Clone> {
+    inner: IDAssigner<T>,
 }
 
-impl<T: Hash + Eq> IDAssigner<T> {
+impl<T: Hash + Eq + Clone> IDAssigner<T> {
     pub fn new() -> Self {
-        Self { data: FxHashMap::default() }
+        Self { data: FxHashMap::default(), names: Vec::new() }
     }
 
     pub fn get_id(&mut self, elem: T) -> u32 {
         if let Some(id) = self.data.get(&elem) {
             *id
         } else {
-            let next_id = self.data.len() as u32;
+            let next_id = self.names.len() as u32;
+            self.names.push(elem.clone());
             self.data.insert(elem, next_id);
             next_id
         }
     }
+
+    // Looks up the ID of an element without assigning a new one
+    pub fn id_of<Q>(&self, elem: &Q) -> Option<u32>
+    where T: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.data.get(elem).copied()
+    }
+
+    pub fn name(&self, id: u32) -> Option<&T> {
+        self.names.get(id as usize)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    // Iterates over the (id, element) pairs in increasing ID order
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> + '_ {
+        self.names.iter().enumerate().map(|(id, name)| (id as u32, name))
+    }
+
+    pub fn freeze(self) -> Interner<T> {
+        Interner { inner: self }
+    }
+}
+
+impl<T: Hash + Eq + Clone> Interner<T> {
+    pub fn id_of<Q>(&self, elem: &Q) -> Option<u32>
+    where T: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.inner.id_of(elem)
+    }
+
+    pub fn name(&self, id: u32) -> Option<&T> {
+        self.inner.name(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> + '_ {
+        self.inner.iter()
+    }
+}
+
+impl<T: Hash + Eq + Clone> Default for IDAssigner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone> Index<u32> for IDAssigner<T> {
+    type Output = T;
+
+    fn index(&self, id: u32) -> &Self::Output {
+        self.name(id).unwrap_or_else(|| panic!("ID not assigned: {id}"))
+    }
+}
+
+impl<T: Hash + Eq + Clone> Index<u32> for Interner<T> {
+    type Output = T;
+
+    fn index(&self, id: u32) -> &Self::Output {
+        &self.inner[id]
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assigns_consecutive_ids_in_order_of_appearance() {
+        let mut ids = IDAssigner::new();
+        assert!(ids.is_empty());
+        assert_eq!(["AA", "BB", "AA", "CC"].map(|s| ids.get_id(s.to_string())), [0, 1, 0, 2]);
+        assert_eq!((ids.len(), ids.id_of("CC"), ids.id_of("DD")), (3, Some(2), None));
+
+        let names = ids.freeze();
+        assert_eq!(names.iter().map(|(id, name)| format!("{id}:{name}")).collect::<Vec<_>>(), ["0:AA", "1:BB", "2:CC"]);
+        assert_eq!((names.name(1), names.name(3)), (Some(&"BB".to_string()), None));
+        assert_eq!((&names[2], names.len(), names.is_empty()), (&"CC".to_string(), 3, false));
+    }
 }
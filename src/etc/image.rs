@@ -0,0 +1,166 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub type Rgb = [u8; 3];
+pub type Gray = u8;
+
+/** A raster image that can be saved as a binary PPM file (with Rgb pixels)
+    or PGM file (with Gray pixels), which most image viewers can open */
+#[derive(Clone, Debug)]
+pub struct Image<P: Pixel> {
+    width: usize,
+    height: usize,
+    pixels: Vec<P>,
+}
+
+/** Writes the frames of a simulation as numbered images in a directory,
+    such as frame_00000.ppm, frame_00001.ppm... */
+pub struct FrameWriter {
+    dir: PathBuf,
+    prefix: String,
+    next: usize,
+}
+
+// The pixel types that the netpbm formats support
+pub trait Pixel: Copy {
+    const MAGIC: &'static str;
+    const EXTENSION: &'static str;
+    fn bytes(&self) -> &[u8];
+}
+
+// Where the solvers save their pictures, if the runner was started with --images
+static OUTPUT_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn enable_output(dir: PathBuf) {
+    fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("Can't create the directory {}: {e}", dir.display()));
+    *OUTPUT_DIR.lock().unwrap() = Some(dir);
+}
+
+// The directory to save pictures in, or None if they're not wanted
+pub fn output_dir() -> Option<PathBuf> {
+    OUTPUT_DIR.lock().unwrap().clone()
+}
+
+// Saves a picture with the given name in the output directory, if there's one.
+// The picture is only drawn when it's going to be saved.
+pub fn save_output<P: Pixel, F: FnOnce() -> Image<P>>(name: &str, draw: F) {
+    let Some(dir) = output_dir() else { return };
+    let path = dir.join(format!("{name}.{}", P::EXTENSION));
+    draw().save(&path).unwrap_or_else(|e| panic!("Can't save {}: {e}", path.display()));
+}
+
+impl<P: Pixel> Image<P> {
+    // Creates an image where every cell of a width x height grid becomes a
+    // square of scale x scale pixels, with the color given by the function
+    pub fn from_fn<F>(width: usize, height: usize, scale: usize, cell_color: F) -> Self
+    where F: Fn(usize, usize) -> P {
+        assert!(scale > 0, "The scale factor must be at least 1");
+        let (width, height) = (width * scale, height * scale);
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| cell_color(x / scale, y / scale))
+            .collect();
+        Self { width, height, pixels }
+    }
+
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "{}\n{} {}\n255\n", P::MAGIC, self.width, self.height)?;
+        for pixel in &self.pixels {
+            out.write_all(pixel.bytes())?;
+        }
+        out.flush()
+    }
+
+    pub fn save<Q: AsRef<Path>>(&self, path: Q) -> io::Result<()> {
+        self.write_to(&mut BufWriter::new(File::create(path)?))
+    }
+}
+
+impl FrameWriter {
+    // Creates the directory if it doesn't exist yet
+    pub fn new<Q: AsRef<Path>>(dir: Q, prefix: &str) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir: dir.as_ref().to_owned(), prefix: prefix.to_owned(), next: 0 })
+    }
+
+    // Saves the next frame, returning the path of the new file
+    pub fn write<P: Pixel>(&mut self, frame: &Image<P>) -> io::Result<PathBuf> {
+        let path = self.dir.join(format!("{}_{:05}.{}", self.prefix, self.next, P::EXTENSION));
+        frame.save(&path)?;
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+impl Pixel for Rgb {
+    const MAGIC: &'static str = "P6";
+    const EXTENSION: &'static str = "ppm";
+
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Pixel for Gray {
+    const MAGIC: &'static str = "P5";
+    const EXTENSION: &'static str = "pgm";
+
+    fn bytes(&self) -> &[u8] {
+        std::slice::from_ref(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<P: Pixel>(image: &Image<P>) -> Vec<u8> {
+        let mut bytes = vec![];
+        image.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn scales_every_cell() {
+        let image = Image::from_fn(3, 2, 2, |x, y| (10 * y + x) as Gray);
+        assert_eq!((image.width, image.height), (6, 4));
+        assert_eq!(&image.pixels[..6], &[0, 0, 1, 1, 2, 2]);
+        assert_eq!(&image.pixels[18..], &[10, 10, 11, 11, 12, 12]);
+    }
+
+    #[test]
+    fn writes_netpbm_headers() {
+        let gray = encode(&Image::from_fn(3, 2, 4, |_, _| 7 as Gray));
+        let header = b"P5\n12 8\n255\n";
+        assert_eq!(&gray[..header.len()], header);
+        assert_eq!(gray.len(), header.len() + 12 * 8);
+
+        let rgb = encode(&Image::from_fn(3, 2, 4, |x, _| [x as u8, 1, 2] as Rgb));
+        let header = b"P6\n12 8\n255\n";
+        assert_eq!(&rgb[..header.len()], header);
+        assert_eq!(rgb.len(), header.len() + 12 * 8 * 3);
+        assert_eq!(&rgb[header.len()..header.len() + 6], &[0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn numbers_the_frames() {
+        let dir = std::env::temp_dir().join(format!("aoc_frames_{}", std::process::id()));
+        let mut frames = FrameWriter::new(&dir, "test").unwrap();
+        let image = Image::from_fn(2, 2, 1, |_, _| [0, 0, 0] as Rgb);
+
+        let first = frames.write(&image).unwrap();
+        let second = frames.write(&image).unwrap();
+        assert_eq!(first, dir.join("test_00000.ppm"));
+        assert_eq!(second, dir.join("test_00001.ppm"));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        assert_eq!(fs::read(&second).unwrap(), encode(&image));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+// Days 4 and 15 don't need every query, gaps and containment are kept so
+// that the set can answer them for other solvers
+#![allow(dead_code)]
+
+use std::fmt::{Display, Formatter};
+use num_traits::int::PrimInt;
+
+/** A set of integers stored as sorted, disjoint inclusive intervals. Intervals
+    that overlap or touch each other are merged as soon as they are added,
+    so [1, 3] and [4, 6] are stored as [1, 6]. */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet<T: PrimInt> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    // Creates a set that contains the values between start and end, both inclusive
+    pub fn single(start: T, end: T) -> Self {
+        let mut set = Self::new();
+        set.insert(start, end);
+        set
+    }
+
+    // Adds the values between start and end, both inclusive, merging any
+    // intervals that touch the new one. Empty intervals (start > end) are ignored.
+    pub fn insert(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        // The intervals in first..last are the ones that overlap or touch the new one
+        let first = self.ranges.partition_point(|&(_, e)| e.saturating_add(T::one()) < start);
+        let last = self.ranges.partition_point(|&(s, _)| s <= end.saturating_add(T::one()));
+
+        let (mut start, mut end) = (start, end);
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    // Iterates over the disjoint intervals, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    // Amount of disjoint intervals in the set
+    pub fn num_intervals(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // Amount of values in the set, that is, the length of the union of every interval
+    pub fn len(&self) -> T {
+        self.ranges.iter().fold(T::zero(), |acc, &(s, e)| acc + (e - s) + T::one())
+    }
+
+    // Lowest and highest values in the set
+    pub fn bounds(&self) -> Option<(T, T)> {
+        Some((self.ranges.first()?.0, self.ranges.last()?.1))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.contains_interval(value, value)
+    }
+
+    // Checks whether every value between start and end, inclusive, is in the set
+    pub fn contains_interval(&self, start: T, end: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        self.ranges.get(i).is_some_and(|&(s, e)| s <= start && end <= e)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.iter().all(|(s, e)| self.contains_interval(s, e))
+    }
+
+    // The intervals between consecutive intervals of the set
+    pub fn gaps(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges.windows(2).map(|w| (w[0].1 + T::one(), w[1].0 - T::one()))
+    }
+
+    // The values between min and max, inclusive, that are not in the set
+    pub fn complement(&self, min: T, max: T) -> Self {
+        let mut result = Self::new();
+        let mut next = Some(min);
+
+        for &(s, e) in self.ranges.iter().skip_while(|&&(_, e)| e < min) {
+            let Some(from) = next else { break };
+            if s > max {
+                break;
+            }
+            if s > from {
+                result.ranges.push((from, s - T::one()));
+            }
+            // There's nothing left to add after the maximum value of T
+            next = e.checked_add(&T::one());
+        }
+
+        if let Some(from) = next.filter(|&from| from <= max) {
+            result.ranges.push((from, max));
+        }
+
+        result
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Self::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let (start, end) = (a.0.max(b.0), a.1.min(b.1));
+            if start <= end {
+                result.ranges.push((start, end));
+            }
+
+            // Move past whichever interval ends first
+            if a.1 < b.1 { i += 1 } else { j += 1 }
+        }
+
+        result
+    }
+}
+
+// Finds the highest amount of sets that have a value in common, along with every
+// value in that many sets. Between two consecutive points where an interval starts
+// or ends, every value is in the same sets, so only those pieces need to be checked.
+pub fn max_coverage<'a, T, I>(sets: I) -> (usize, IntervalSet<T>)
+where T: PrimInt + 'a, I: IntoIterator<Item = &'a IntervalSet<T>> {
+    let sets: Vec<_> = sets.into_iter().collect();
+    let mut bounds: Vec<T> = sets.iter()
+        .flat_map(|set| set.iter())
+        .flat_map(|(s, e)| [Some(s), e.checked_add(&T::one())])
+        .flatten()
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut best = (0, IntervalSet::new());
+    for (i, &start) in bounds.iter().enumerate() {
+        let end = bounds.get(i + 1).map_or(T::max_value(), |&next| next - T::one());
+        let count = sets.iter().filter(|set| set.contains_interval(start, end)).count();
+
+        if count > best.0 {
+            best = (count, IntervalSet::new());
+        }
+        if count == best.0 && count > 0 {
+            best.1.insert(start, end);
+        }
+    }
+
+    best
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Shows the intervals like "1-3, 5, 7-9"
+impl<T: PrimInt + Display> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, (s, e)) in self.iter().enumerate() {
+            let sep = if i > 0 { ", " } else { "" };
+            if s == e { write!(f, "{sep}{s}")? } else { write!(f, "{sep}{s}-{e}")? }
+        }
+        Ok(())
+    }
+}
+
+// Sorting everything first and merging afterwards is faster than
+// inserting the intervals one by one
+impl<T: PrimInt> FromIterator<(T, T)> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut sorted: Vec<(T, T)> = iter.into_iter().filter(|&(s, e)| s <= e).collect();
+        sorted.sort_unstable();
+
+        let mut ranges: Vec<(T, T)> = Vec::with_capacity(sorted.len());
+        for (s, e) in sorted {
+            match ranges.last_mut() {
+                Some(last) if s <= last.1.saturating_add(T::one()) => last.1 = last.1.max(e),
+                _ => ranges.push((s, e)),
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+impl<T: PrimInt> Extend<(T, T)> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, iter: I) {
+        for (s, e) in iter {
+            self.insert(s, e);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().copied().collect()
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_touching_intervals() {
+        let mut s = IntervalSet::new();
+        s.insert(1, 3);
+        s.insert(7, 9);
+        s.insert(5, 5);
+        assert_eq!(s.iter().collect::<Vec<_>>(), [(1, 3), (5, 5), (7, 9)]);
+
+        s.insert(4, 4);
+        assert_eq!(s.iter().collect::<Vec<_>>(), [(1, 5), (7, 9)]);
+        s.insert(6, 6);
+        assert_eq!(s.iter().collect::<Vec<_>>(), [(1, 9)]);
+
+        s.insert(3, 2);
+        assert_eq!(s.num_intervals(), 1);
+        assert_eq!(s, set(&[(7, 9), (1, 6), (5, 8), (4, 2)]));
+    }
+
+    #[test]
+    fn insert_touches_at_the_limits_of_the_type() {
+        let mut s = IntervalSet::single(u8::MAX - 1, u8::MAX);
+        s.insert(u8::MAX, u8::MAX);
+        s.insert(250, 253);
+        assert_eq!(s.iter().collect::<Vec<_>>(), [(250, 255)]);
+
+        let mut s = IntervalSet::single(i8::MIN, i8::MIN);
+        s.insert(i8::MIN + 1, 0);
+        s.insert(i8::MAX, i8::MAX);
+        assert_eq!(s.iter().collect::<Vec<_>>(), [(i8::MIN, 0), (i8::MAX, i8::MAX)]);
+        assert_eq!(s, [(i8::MAX, i8::MAX), (i8::MIN, -1), (-2, 0)].into_iter().collect());
+    }
+
+    #[test]
+    fn queries() {
+        let s = set(&[(1, 3), (6, 6), (10, 12)]);
+        assert_eq!(s.len(), 7);
+        assert_eq!(s.bounds(), Some((1, 12)));
+        assert!(s.contains(6) && !s.contains(5) && !s.contains(13));
+        assert!(s.contains_interval(10, 12) && !s.contains_interval(3, 6));
+        assert!(s.is_superset(&set(&[(2, 3), (11, 11)])));
+        assert!(!s.is_superset(&set(&[(2, 4)])));
+        assert_eq!(IntervalSet::<i32>::new().bounds(), None);
+    }
+
+    #[test]
+    fn gaps_between_intervals() {
+        let s = set(&[(1, 3), (6, 6), (8, 12)]);
+        assert_eq!(s.gaps().collect::<Vec<_>>(), [(4, 5), (7, 7)]);
+        assert_eq!(set(&[(1, 3)]).gaps().count(), 0);
+        assert_eq!(IntervalSet::<i32>::new().gaps().count(), 0);
+    }
+
+    #[test]
+    fn complement_within_bounds() {
+        let s = set(&[(1, 3), (6, 6), (10, 12)]);
+        assert_eq!(s.complement(0, 11), set(&[(0, 0), (4, 5), (7, 9)]));
+        assert_eq!(s.complement(2, 6), set(&[(4, 5)]));
+        assert_eq!(s.complement(4, 5), set(&[(4, 5)]));
+        assert_eq!(s.complement(20, 30), set(&[(20, 30)]));
+        assert!(s.complement(10, 12).is_empty());
+
+        // An empty range has nothing to complement
+        assert!(s.complement(8, 7).is_empty());
+        assert!(IntervalSet::<i32>::new().complement(5, 0).is_empty());
+    }
+
+    #[test]
+    fn complement_at_the_limits_of_the_type() {
+        let s = IntervalSet::single(250_u8, u8::MAX);
+        assert_eq!(s.complement(0, u8::MAX), IntervalSet::single(0, 249));
+        assert!(s.complement(250, u8::MAX).is_empty());
+
+        let s = IntervalSet::single(i64::MIN, -1);
+        assert_eq!(s.complement(i64::MIN, i64::MAX), IntervalSet::single(0, i64::MAX));
+        assert_eq!(IntervalSet::new().complement(i64::MIN, i64::MAX), IntervalSet::single(i64::MIN, i64::MAX));
+    }
+
+    #[test]
+    fn union_and_intersection() {
+        let a = set(&[(1, 5), (10, 15), (20, 20)]);
+        let b = set(&[(3, 11), (15, 20)]);
+        assert_eq!(a.union(&b), set(&[(1, 20)]));
+        assert_eq!(a.intersection(&b), set(&[(3, 5), (10, 11), (15, 15), (20, 20)]));
+        assert_eq!(b.intersection(&a), a.intersection(&b));
+
+        let c = set(&[(6, 9), (21, 30)]);
+        assert!(a.intersection(&c).is_empty());
+        assert!(a.intersection(&IntervalSet::new()).is_empty());
+        assert_eq!(a.intersection(&a), a);
+    }
+
+    #[test]
+    fn max_coverage_counts_sets() {
+        assert_eq!(max_coverage::<i32, _>([]), (0, IntervalSet::new()));
+
+        let sets = [set(&[(1, 4), (8, 9)]), set(&[(3, 8)]), set(&[(0, 3), (9, 9)])];
+        assert_eq!(max_coverage(&sets), (3, set(&[(3, 3)])));
+
+        let sets = [IntervalSet::single(0_u8, u8::MAX), IntervalSet::single(200, u8::MAX)];
+        assert_eq!(max_coverage(&sets), (2, IntervalSet::single(200, u8::MAX)));
+    }
+}
@@ -2,10 +2,23 @@ pub mod solution;
 pub mod utils;
 pub mod vecmat;
 pub mod coords;
+pub mod coords_n;
+pub mod voxel_grid;
 pub mod id_assigner;
-pub mod maybe_val;
+pub mod expr;
+pub mod rational;
+pub mod checked;
+pub mod cycle;
+pub mod intervals;
+pub mod parsing;
+pub mod sparse_grid;
+pub mod search;
+pub mod image;
+pub mod bitset;
+pub mod visualize;
 
 pub use solution::Solution;
-pub use utils::DOUBLE_NEWLINE;
+pub use utils::read_input;
 pub use coords::Coords;
-pub use vecmat::VecMat;
\ No newline at end of file
+pub use vecmat::VecMat;
+pub use sparse_grid::SparseGrid;
\ No newline at end of file
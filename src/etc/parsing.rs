@@ -0,0 +1,317 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use regex::{Captures, Regex};
+
+/** A line template such as "move {usize} from {usize} to {usize}", that
+    extracts the values in its placeholders as a tuple of typed values.
+
+    Every placeholder only matches text that looks like its kind:
+    - Integer types ({u32}, {i64}, {usize}...) match an optionally signed number
+    - {char} matches a single character
+    - {word} matches a run of non-whitespace characters
+    - {str} matches any text, as little as possible, up to the rest of the template
+    - {_} matches a word like {word} but doesn't capture it
+
+    Whitespace in the template matches one or more whitespace characters in the
+    line, so "{u32} {u32}" doesn't match "1234". Next to punctuation it becomes
+    optional, so "x = {i32}," matches "x=3," as well as "x = 3 ,". Leading and
+    trailing whitespace is ignored. */
+pub struct Pattern {
+    template: String,
+    parts: Vec<Part>,
+    regex: Regex,
+}
+
+enum Part {
+    // Never contains whitespace, which is split into its own parts
+    Literal(String),
+    Placeholder(String),
+    // Whether at least one whitespace character is needed
+    Space { required: bool },
+}
+
+pub struct ParseError {
+    message: String,
+}
+
+// Types that can be built from the captured text of a pattern
+pub trait FromCaptures: Sized {
+    fn from_captures(values: &[&str], kinds: &[&str]) -> Result<Self, String>;
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl Pattern {
+    pub fn new(template: &str) -> Self {
+        let parts = split_template(template);
+        let regex = Regex::new(&format!("^{}$", to_regex(&parts))).unwrap();
+        Self { template: template.to_owned(), parts, regex }
+    }
+
+    pub fn parse<T: FromCaptures>(&self, line: &str) -> Result<T, ParseError> {
+        let captures = self.regex.captures(line).ok_or_else(|| self.explain_mismatch(line))?;
+        let values = capture_values(&captures);
+        let kinds = self.capture_kinds();
+
+        T::from_captures(&values, &kinds).map_err(|msg| ParseError {
+            message: format!("Can't parse {line:?} with pattern {:?}: {msg}", self.template)
+        })
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn capture_kinds(&self) -> Vec<&str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Placeholder(kind) if kind != "_" => Some(kind.as_str()),
+            _ => None,
+        }).collect()
+    }
+
+    // Finds out which is the first part of the template that doesn't match,
+    // and how far along the line we got before that
+    fn explain_mismatch(&self, line: &str) -> ParseError {
+        let mut matched_until = 0;
+
+        for i in 1..=self.parts.len() {
+            let regex = Regex::new(&format!("^{}", to_regex(&self.parts[..i]))).unwrap();
+            match regex.find(line) {
+                Some(m) if i < self.parts.len() => matched_until = m.end(),
+                Some(m) => {
+                    matched_until = m.end();
+                    break;
+                },
+                None => {
+                    let expected = match &self.parts[i - 1] {
+                        Part::Literal(text) => format!("{text:?}"),
+                        Part::Placeholder(kind) => format!("a {{{kind}}}"),
+                        Part::Space { .. } => "whitespace".to_owned(),
+                    };
+                    return ParseError {
+                        message: format!("Can't parse {line:?} with pattern {:?}: expected {expected} at column {}, found {:?}",
+                                         self.template, matched_until + 1, &line[matched_until..])
+                    };
+                },
+            }
+        }
+
+        ParseError {
+            message: format!("Can't parse {line:?} with pattern {:?}: unexpected {:?} at column {}",
+                             self.template, &line[matched_until..], matched_until + 1)
+        }
+    }
+}
+
+// Extracts all the integers found in a line, in order, ignoring any text around them.
+// A minus sign counts as part of a number unless it comes right after a digit,
+// so that ranges such as "2-4" are read as two positive numbers.
+pub fn extract_ints<T: FromCaptures>(line: &str) -> Result<T, ParseError> {
+    let numbers = find_ints(line);
+    let kinds = vec!["integer"; numbers.len()];
+
+    T::from_captures(&numbers, &kinds).map_err(|msg| ParseError {
+        message: format!("Can't extract integers from {line:?}: {msg}")
+    })
+}
+
+fn find_ints(line: &str) -> Vec<&str> {
+    let bytes = line.as_bytes();
+    let mut numbers = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let is_sign = bytes[i] == b'-' && (i == 0 || !bytes[i - 1].is_ascii_digit())
+                      && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+
+        if is_sign || bytes[i].is_ascii_digit() {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            numbers.push(&line[start..i]);
+        } else {
+            i += 1;
+        }
+    }
+
+    numbers
+}
+
+// Splits the template into literal text, placeholders and the whitespace
+// between them. Punctuation is separated from its neighbours by optional
+// whitespace even if there's none in the template, as in "rate={u32}".
+fn split_template(template: &str) -> Vec<Part> {
+    let mut parts: Vec<Part> = vec![];
+    let mut rest = template.trim();
+    let mut spaced = false;
+
+    while !rest.is_empty() {
+        let part = if let Some(after) = rest.strip_prefix('{') {
+            let end = after.find('}').unwrap_or_else(|| panic!("Unclosed placeholder in {template:?}"));
+            rest = &after[end + 1..];
+            Part::Placeholder(after[..end].trim().to_owned())
+        } else if rest.starts_with(char::is_whitespace) {
+            rest = rest.trim_start();
+            spaced = true;
+            continue;
+        } else {
+            // Every punctuation character is a part on its own, so that
+            // "y={i32}" can match "y = 3" as well as "y=3"
+            let end = if rest.starts_with(|ch: char| ch.is_ascii_punctuation()) { 1 } else {
+                rest.find(|ch: char| ch.is_whitespace() || ch.is_ascii_punctuation()).unwrap_or(rest.len())
+            };
+            let text = rest[..end].to_owned();
+            rest = &rest[end..];
+            Part::Literal(text)
+        };
+
+        if let Some(prev) = parts.last() {
+            let punctuated = ends_with_punctuation(prev) || starts_with_punctuation(&part);
+            if spaced || punctuated {
+                parts.push(Part::Space { required: !punctuated });
+            }
+        }
+        parts.push(part);
+        spaced = false;
+    }
+
+    parts
+}
+
+fn starts_with_punctuation(part: &Part) -> bool {
+    matches!(part, Part::Literal(text) if text.starts_with(|ch: char| ch.is_ascii_punctuation()))
+}
+
+fn ends_with_punctuation(part: &Part) -> bool {
+    matches!(part, Part::Literal(text) if text.ends_with(|ch: char| ch.is_ascii_punctuation()))
+}
+
+fn to_regex(parts: &[Part]) -> String {
+    let body: String = parts.iter().map(|part| match part {
+        Part::Literal(text) => regex::escape(text),
+        Part::Placeholder(kind) => kind_regex(kind).to_owned(),
+        Part::Space { required: true } => r"\s+".to_owned(),
+        Part::Space { required: false } => r"\s*".to_owned(),
+    }).collect();
+
+    format!(r"\s*{body}\s*")
+}
+
+fn kind_regex(kind: &str) -> &'static str {
+    match kind {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => r"(\+?\d+)",
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => r"([+-]?\d+)",
+        "char" => r"(.)",
+        "word" => r"(\S+)",
+        "str" => r"(.*?)",
+        "_" => r"\S+",
+        _ => panic!("Unknown placeholder kind: {{{kind}}}"),
+    }
+}
+
+fn capture_values<'a>(captures: &Captures<'a>) -> Vec<&'a str> {
+    captures.iter().skip(1).map(|m| m.map_or("", |m| m.as_str())).collect()
+}
+
+fn parse_value<T: FromStr>(values: &[&str], kinds: &[&str], i: usize) -> Result<T, String> {
+    values[i].parse().map_err(|_| {
+        format!("value #{} ({:?}) is not a valid {{{}}}", i + 1, values[i], kinds[i])
+    })
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! impl_from_captures {
+    ($n_:literal; $($type_:ident $index_:tt),+) => {
+        impl<$($type_: FromStr),+> FromCaptures for ($($type_,)+) {
+            fn from_captures(values: &[&str], kinds: &[&str]) -> Result<Self, String> {
+                if values.len() != $n_ {
+                    return Err(format!("found {} values but {} were expected", values.len(), $n_));
+                }
+                Ok(($(parse_value::<$type_>(values, kinds, $index_)?,)+))
+            }
+        }
+    }
+}
+
+impl<T: FromStr, const N: usize> FromCaptures for [T; N] {
+    fn from_captures(values: &[&str], kinds: &[&str]) -> Result<Self, String> {
+        if values.len() != N {
+            return Err(format!("found {} values but {} were expected", values.len(), N));
+        }
+        let parsed = (0..N).map(|i| parse_value(values, kinds, i)).collect::<Result<Vec<T>, _>>()?;
+        Ok(parsed.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+impl_from_captures!(1; A 0);
+impl_from_captures!(2; A 0, B 1);
+impl_from_captures!(3; A 0, B 1, C 2);
+impl_from_captures!(4; A 0, B 1, C 2, D 3);
+impl_from_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_captures!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_captures!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+// Same as Display, so that unwrapping a failed parse shows a readable message
+impl Debug for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitespace_is_required_between_words() {
+        let pattern = Pattern::new("{u32} {u32}");
+        assert_eq!(pattern.parse::<(u32, u32)>("12  34").unwrap(), (12, 34));
+        assert_eq!(pattern.parse::<(u32, u32)>("  12\t34 ").unwrap(), (12, 34));
+        assert!(pattern.parse::<(u32, u32)>("1234").is_err());
+
+        let pattern = Pattern::new("move {usize} from {usize}");
+        assert!(pattern.parse::<(usize, usize)>("move 1from 2").is_err());
+        assert!(pattern.parse::<(usize, usize)>("move1 from 2").is_err());
+    }
+
+    #[test]
+    fn whitespace_is_optional_next_to_punctuation() {
+        let pattern = Pattern::new("x = {i32}, y={i32}");
+        assert_eq!(pattern.parse::<(i32, i32)>("x=3,y=-4").unwrap(), (3, -4));
+        assert_eq!(pattern.parse::<(i32, i32)>("x = 3 , y = -4").unwrap(), (3, -4));
+
+        let pattern = Pattern::new("Valve {word} has flow rate={u32}; {_} {_} to {_} {str}");
+        let (name, rate, rest): (String, u32, String) = pattern
+            .parse("Valve AA has flow rate=0; tunnels lead to valves DD, II, BB").unwrap();
+        assert_eq!((name.as_str(), rate, rest.as_str()), ("AA", 0, "DD, II, BB"));
+    }
+
+    #[test]
+    fn explains_what_was_expected() {
+        let error = Pattern::new("{u32} {u32}").parse::<(u32, u32)>("12x").unwrap_err();
+        assert!(error.to_string().contains("expected whitespace at column 3"), "{error}");
+
+        let error = Pattern::new("move {usize} from {usize}").parse::<(usize, usize)>("move 1 to 2").unwrap_err();
+        assert!(error.to_string().contains("expected \"from\" at column 8"), "{error}");
+
+        let error = Pattern::new("x={i32}").parse::<(i32,)>("x: 3").unwrap_err();
+        assert!(error.to_string().contains("expected \"=\" at column 2"), "{error}");
+    }
+
+    #[test]
+    fn extracts_signed_integers() {
+        assert_eq!(find_ints("x=-3, y=12..-4 2-4"), ["-3", "12", "-4", "2", "4"]);
+        assert_eq!(extract_ints::<[i32; 3]>("a-1b 2c3").unwrap(), [-1, 2, 3]);
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use num_traits::int::PrimInt;
+use num_traits::sign::Signed;
+use num_traits::CheckedRem;
+
+/** An exact fraction, always stored in lowest terms and with a positive
+    denominator. Arithmetic panics on overflow instead of wrapping around,
+    use the checked_* methods to handle it gracefully. */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational<T: PrimInt + Signed + CheckedRem = i128> {
+    num: T,
+    den: T,
+}
+
+impl<T: PrimInt + Signed + CheckedRem + Display> Rational<T> {
+    // Returns None if the denominator is zero or the value can't be represented
+    pub fn checked_new(num: T, den: T) -> Option<Self> {
+        if den.is_zero() {
+            return None;
+        }
+
+        let div = gcd(num, den)?;
+        let (num, den) = (num / div, den / div);
+
+        if den < T::zero() {
+            Some(Self { num: T::zero().checked_sub(&num)?, den: T::zero().checked_sub(&den)? })
+        } else {
+            Some(Self { num, den })
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den.is_one()
+    }
+
+    // Returns the value as an integer, only if it doesn't have a fractional part
+    pub fn to_integer(self) -> Option<T> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        // Work with the least common multiple of the denominators to keep the numbers small
+        let g = gcd(self.den, rhs.den)?;
+        let left = self.num.checked_mul(&(rhs.den / g))?;
+        let right = rhs.num.checked_mul(&(self.den / g))?;
+        Self::checked_new(left.checked_add(&right)?, (self.den / g).checked_mul(&rhs.den)?)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // Cross-simplify first, so that the products are as small as possible
+        let g1 = gcd(self.num, rhs.den)?;
+        let g2 = gcd(rhs.num, self.den)?;
+        let num = (self.num / g1).checked_mul(&(rhs.num / g2))?;
+        let den = (self.den / g2).checked_mul(&(rhs.den / g1))?;
+        Self::checked_new(num, den)
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(rhs.checked_recip()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self { num: T::zero().checked_sub(&self.num)?, den: self.den })
+    }
+
+    // Returns None when trying to invert zero
+    pub fn checked_recip(self) -> Option<Self> {
+        Self::checked_new(self.den, self.num)
+    }
+}
+
+// Returns None if the result doesn't fit in T, which can only
+// happen when T::MIN is involved, as in gcd(T::MIN, T::MIN)
+fn gcd<T: PrimInt + Signed + CheckedRem>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a, b);
+    while !b.is_zero() {
+        (a, b) = (b, a.checked_rem(&b)?);
+    }
+
+    match a {
+        a if a.is_zero() => Some(T::one()),
+        a if a.is_negative() => T::zero().checked_sub(&a),
+        a => Some(a),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T: PrimInt + Signed + CheckedRem> From<T> for Rational<T> {
+    fn from(x: T) -> Self {
+        Self { num: x, den: T::one() }
+    }
+}
+
+impl<T: PrimInt + Signed + CheckedRem + Display> Display for Rational<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+macro_rules! impl_op {
+    ($trait_:ident, $func_:ident, $checked_:ident, $symbol_:literal) => {
+        impl<T: PrimInt + Signed + CheckedRem + Display> $trait_ for Rational<T> {
+            type Output = Self;
+
+            fn $func_(self, rhs: Self) -> Self::Output {
+                self.$checked_(rhs).unwrap_or_else(|| panic!("Invalid operation: ({self}) {} ({rhs})", $symbol_))
+            }
+        }
+    }
+}
+
+impl_op!(Add, add, checked_add, "+");
+impl_op!(Sub, sub, checked_sub, "-");
+impl_op!(Mul, mul, checked_mul, "*");
+impl_op!(Div, div, checked_div, "/");
+
+impl<T: PrimInt + Signed + CheckedRem + Display> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().unwrap_or_else(|| panic!("Overflow negating {self}"))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(num: i64, den: i64) -> Rational<i64> {
+        Rational::checked_new(num, den).unwrap()
+    }
+
+    #[test]
+    fn reduces_to_lowest_terms() {
+        assert_eq!(frac(6, -4).to_string(), "-3/2");
+        assert_eq!(frac(0, -7), Rational::from(0));
+        assert_eq!(frac(1, 3) + frac(1, 6), frac(1, 2));
+        assert_eq!(frac(2, 3) * frac(3, 4), frac(1, 2));
+        assert_eq!(frac(8, 4).to_integer(), Some(2));
+    }
+
+    #[test]
+    fn overflow_at_min_is_none() {
+        assert_eq!(Rational::checked_new(i128::MIN, -1), None);
+        assert_eq!(Rational::checked_new(i128::MIN, 0), None);
+        assert_eq!(Rational::from(i128::MIN).checked_neg(), None);
+        assert_eq!(Rational::checked_new(i128::MIN, 2), Some(Rational::from(i128::MIN / 2)));
+        assert_eq!(Rational::from(i128::MIN).checked_mul(Rational::from(-1)), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,218 @@
+// Dijkstra is part of the toolkit for weighted graphs, even if no solver
+// currently needs it without a heuristic
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use std::iter::once;
+
+use num_traits::int::PrimInt;
+use rustc_hash::FxHashMap;
+
+/** Outcome of a graph search: the cost to reach every explored node, the
+    node from which each one was reached, and the goal if one was found */
+pub struct SearchResult<N: Copy + Hash + Eq, C: PrimInt> {
+    pub distances: FxHashMap<N, C>,
+    pub predecessors: FxHashMap<N, N>,
+    pub goal: Option<N>,
+}
+
+// Entry in the priority queue used by Dijkstra and A*. Entries are compared
+// only by their priority, and in reverse, so that the heap pops the lowest first.
+struct HeapEntry<N, C: PrimInt> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Breadth-first search from a single node, stopping as soon as a node
+// that satisfies the success condition is reached
+pub fn bfs<N, FN, IN, FS>(start: N, neighbors: FN, success: FS) -> SearchResult<N, usize>
+where N: Copy + Hash + Eq,
+      FN: FnMut(N) -> IN,
+      IN: IntoIterator<Item = N>,
+      FS: FnMut(N) -> bool {
+    bfs_multi(once(start), neighbors, success)
+}
+
+// Breadth-first search from several nodes at once, which is equivalent to
+// running it from a virtual node connected to all of them
+pub fn bfs_multi<N, IS, FN, IN, FS>(starts: IS, mut neighbors: FN, mut success: FS) -> SearchResult<N, usize>
+where N: Copy + Hash + Eq,
+      IS: IntoIterator<Item = N>,
+      FN: FnMut(N) -> IN,
+      IN: IntoIterator<Item = N>,
+      FS: FnMut(N) -> bool {
+    let mut result = SearchResult::new();
+    let mut to_visit = VecDeque::new();
+
+    for start in starts {
+        if result.distances.insert(start, 0).is_none() {
+            to_visit.push_back(start);
+        }
+    }
+
+    while let Some(node) = to_visit.pop_front() {
+        if success(node) {
+            result.goal = Some(node);
+            break;
+        }
+
+        let next_dist = result.distances[&node] + 1;
+        for next_node in neighbors(node) {
+            if let Entry::Vacant(entry) = result.distances.entry(next_node) {
+                entry.insert(next_dist);
+                result.predecessors.insert(next_node, node);
+                to_visit.push_back(next_node);
+            }
+        }
+    }
+
+    result
+}
+
+// Dijkstra's algorithm, for graphs with non-negative edge costs
+pub fn dijkstra<N, C, FN, IN, FS>(start: N, neighbors: FN, success: FS) -> SearchResult<N, C>
+where N: Copy + Hash + Eq,
+      C: PrimInt,
+      FN: FnMut(N) -> IN,
+      IN: IntoIterator<Item = (N, C)>,
+      FS: FnMut(N) -> bool {
+    astar(start, neighbors, |_| C::zero(), success)
+}
+
+// A* search. The heuristic must never overestimate the remaining
+// cost to the goal, otherwise the path found may not be the shortest.
+pub fn astar<N, C, FN, IN, FH, FS>(start: N, mut neighbors: FN, mut heuristic: FH, mut success: FS) -> SearchResult<N, C>
+where N: Copy + Hash + Eq,
+      C: PrimInt,
+      FN: FnMut(N) -> IN,
+      IN: IntoIterator<Item = (N, C)>,
+      FH: FnMut(N) -> C,
+      FS: FnMut(N) -> bool {
+    let mut result = SearchResult::new();
+    let mut to_visit = BinaryHeap::new();
+
+    result.distances.insert(start, C::zero());
+    to_visit.push(HeapEntry { priority: heuristic(start), cost: C::zero(), node: start });
+
+    while let Some(HeapEntry { cost, node, .. }) = to_visit.pop() {
+        // Skip outdated entries, for nodes that were reached later through a cheaper path
+        if cost > result.distances[&node] {
+            continue;
+        }
+
+        if success(node) {
+            result.goal = Some(node);
+            break;
+        }
+
+        for (next_node, edge_cost) in neighbors(node) {
+            let new_cost = cost + edge_cost;
+            let improves = result.distances.get(&next_node).is_none_or(|&prev| new_cost < prev);
+
+            if improves {
+                result.distances.insert(next_node, new_cost);
+                result.predecessors.insert(next_node, node);
+                to_visit.push(HeapEntry { priority: new_cost + heuristic(next_node), cost: new_cost, node: next_node });
+            }
+        }
+    }
+
+    result
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Copy + Hash + Eq, C: PrimInt> SearchResult<N, C> {
+    fn new() -> Self {
+        Self { distances: FxHashMap::default(), predecessors: FxHashMap::default(), goal: None }
+    }
+
+    // Cost of reaching the goal, if it was found
+    pub fn cost(&self) -> Option<C> {
+        self.goal.map(|goal| self.distances[&goal])
+    }
+
+    // Sequence of nodes from the start to the goal, both inclusive
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.goal.and_then(|goal| self.path_to(goal))
+    }
+
+    // Sequence of nodes from the start to any explored node, both inclusive
+    pub fn path_to(&self, target: N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(&target) {
+            return None;
+        }
+
+        let mut path = vec![target];
+        while let Some(prev) = self.predecessors.get(path.last().unwrap()) {
+            path.push(*prev);
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<N, C: PrimInt> PartialEq for HeapEntry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: PrimInt> Eq for HeapEntry<N, C> { }
+
+impl<N, C: PrimInt> PartialOrd for HeapEntry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: PrimInt> Ord for HeapEntry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -1-> b -1-> c -1-> d, plus a shortcut a -5-> d and a detour b -1-> e -1-> d
+    fn edges(node: char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('d', 5)],
+            'b' => vec![('c', 1), ('e', 1)],
+            'c' => vec![('d', 2)],
+            'e' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_and_astar_find_the_cheapest_path() {
+        let result = dijkstra('a', edges, |n| n == 'd');
+        assert_eq!((result.cost(), result.path()), (Some(3), Some(vec!['a', 'b', 'e', 'd'])));
+
+        let result = astar('a', edges, |n| if n == 'd' { 0 } else { 1 }, |n| n == 'd');
+        assert_eq!((result.cost(), result.path()), (Some(3), Some(vec!['a', 'b', 'e', 'd'])));
+    }
+
+    #[test]
+    fn bfs_counts_steps_instead_of_costs() {
+        let result = bfs('a', |n| edges(n).into_iter().map(|(next, _)| next), |n| n == 'd');
+        assert_eq!((result.cost(), result.path()), (Some(1), Some(vec!['a', 'd'])));
+
+        let result = bfs_multi(['c', 'e'], |n| edges(n).into_iter().map(|(next, _)| next), |_| false);
+        assert_eq!((result.goal, result.distances.len()), (None, 3));
+        assert_eq!(result.path_to('d').map(|p| p.len()), Some(2));
+        assert_eq!(result.path_to('a'), None);
+    }
+}
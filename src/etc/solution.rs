@@ -16,6 +16,15 @@ pub enum Solution {
     U128(u128),
     Usize(usize),
     Str(String),
+    // Any of the above, along with some details on how it was found
+    Noted(Box<Solution>, String),
+}
+
+impl Solution {
+    // Adds some details to show next to the answer, between parentheses
+    pub fn with_note(self, note: impl Into<String>) -> Self {
+        Noted(Box::new(self), note.into())
+    }
 }
 
 impl Display for Solution {
@@ -34,6 +43,7 @@ impl Display for Solution {
             U128(x) => x.fmt(f),
             Usize(x) => x.fmt(f),
             Str(x) => x.fmt(f),
+            Noted(x, note) => write!(f, "{x} ({note})"),
         }
     }
 }
@@ -0,0 +1,284 @@
+// Not every query and conversion is used by days 14, 17 and 23, they are
+// kept so that the grid can be used like the dense VecMat
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+use num_traits::int::PrimInt;
+use num_traits::sign::Signed;
+use rustc_hash::FxHashMap;
+
+use super::coords::Coords;
+use super::vecmat::VecMat;
+use super::image::{Image, Pixel};
+
+/** An unbounded 2D grid that only stores its occupied positions, and keeps
+    track of the bounding box of its contents as they are added and removed */
+#[derive(Clone, Debug)]
+pub struct SparseGrid<I: PrimInt + Signed + Hash, V = ()> {
+    cells: FxHashMap<Coords<I>, V>,
+    // Amount of occupied cells in every column and row, so that the
+    // bounds can be updated without scanning the whole grid
+    columns: BTreeMap<I, usize>,
+    rows: BTreeMap<I, usize>,
+}
+
+impl<I: PrimInt + Signed + Hash, V> SparseGrid<I, V> {
+    pub fn new() -> Self {
+        Self { cells: FxHashMap::default(), columns: BTreeMap::new(), rows: BTreeMap::new() }
+    }
+
+    // Stores a value in the given position, returning the previous one if there was any
+    pub fn insert(&mut self, pos: Coords<I>, value: V) -> Option<V> {
+        let prev = self.cells.insert(pos, value);
+        if prev.is_none() {
+            *self.columns.entry(pos.x).or_insert(0) += 1;
+            *self.rows.entry(pos.y).or_insert(0) += 1;
+        }
+        prev
+    }
+
+    pub fn remove(&mut self, pos: &Coords<I>) -> Option<V> {
+        let prev = self.cells.remove(pos);
+        if prev.is_some() {
+            decrement(&mut self.columns, pos.x);
+            decrement(&mut self.rows, pos.y);
+        }
+        prev
+    }
+
+    pub fn get(&self, pos: &Coords<I>) -> Option<&V> {
+        self.cells.get(pos)
+    }
+
+    pub fn get_mut(&mut self, pos: &Coords<I>) -> Option<&mut V> {
+        self.cells.get_mut(pos)
+    }
+
+    pub fn contains(&self, pos: &Coords<I>) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coords<I>, &V)> + '_ {
+        self.cells.iter().map(|(pos, val)| (*pos, val))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Coords<I>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    // Returns the top-left and bottom-right corners of the smallest
+    // rectangle that contains every occupied cell, both inclusive
+    pub fn bounds(&self) -> Option<(Coords<I>, Coords<I>)> {
+        let (min_x, max_x) = (self.columns.keys().next()?, self.columns.keys().next_back()?);
+        let (min_y, max_y) = (self.rows.keys().next()?, self.rows.keys().next_back()?);
+        Some((Coords::new(*min_x, *min_y), Coords::new(*max_x, *max_y)))
+    }
+
+    pub fn width(&self) -> I {
+        self.bounds().map_or(I::zero(), |(min, max)| max.x - min.x + I::one())
+    }
+
+    pub fn height(&self) -> I {
+        self.bounds().map_or(I::zero(), |(min, max)| max.y - min.y + I::one())
+    }
+
+    // Number of cells, occupied or not, within the bounding box
+    pub fn area(&self) -> I {
+        self.width() * self.height()
+    }
+
+    // Occupied cells orthogonally adjacent to the given position
+    pub fn neighbors4(&self, pos: Coords<I>) -> impl Iterator<Item = (Coords<I>, &V)> + '_ {
+        self.occupied(pos.neighbors4())
+    }
+
+    // Occupied cells orthogonally or diagonally adjacent to the given position
+    pub fn neighbors8(&self, pos: Coords<I>) -> impl Iterator<Item = (Coords<I>, &V)> + '_ {
+        self.occupied(pos.neighbors8())
+    }
+
+    // Draws the bounding box of the grid, one line per row, using the
+    // provided function to decide which character represents each cell
+    pub fn render<F>(&self, to_char: F) -> String
+    where F: Fn(Option<&V>) -> char {
+        let Some((min, max)) = self.bounds() else { return String::new() };
+
+        range_inclusive(min.y, max.y).map(|y| {
+            range_inclusive(min.x, max.x).map(|x| to_char(self.get(&Coords::new(x, y)))).collect::<String>()
+        }).collect::<Vec<_>>().join("\n")
+    }
+
+    // Draws the bounding box of the grid, every cell as a square of
+    // scale x scale pixels, with the color given by the function
+    pub fn to_image<P, F>(&self, scale: usize, to_color: F) -> Image<P>
+    where P: Pixel, F: Fn(Option<&V>) -> P {
+        let Some((min, _)) = self.bounds() else { return Image::from_fn(0, 0, scale, |_, _| to_color(None)) };
+        let (width, height) = (self.width().to_usize().unwrap(), self.height().to_usize().unwrap());
+
+        Image::from_fn(width, height, scale, |x, y| {
+            let pos = min + Coords::new(I::from(x).unwrap(), I::from(y).unwrap());
+            to_color(self.get(&pos))
+        })
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn occupied<'a, It>(&'a self, positions: It) -> impl Iterator<Item = (Coords<I>, &'a V)> + 'a
+    where It: Iterator<Item = Coords<I>> + 'a {
+        positions.filter_map(|p| self.get(&p).map(|val| (p, val)))
+    }
+}
+
+impl<I: PrimInt + Signed + Hash, V: Copy> SparseGrid<I, V> {
+    // Builds a sparse grid with the cells of a matrix that satisfy a condition,
+    // with the top-left corner of the matrix placed at the origin
+    pub fn from_vecmat<F>(mat: &VecMat<V>, keep: F) -> Self
+    where F: Fn(V) -> bool {
+        mat.indexed_iter()
+            .filter(|(_, val)| keep(*val))
+            .map(|(pos, val)| (Coords::from_pos2d(pos), val))
+            .collect()
+    }
+
+    // Dumps the bounding box of the grid into a matrix, filling the unoccupied
+    // cells with a default value. The top-left corner of the bounding box
+    // is placed at (0, 0) in the matrix.
+    pub fn to_vecmat(&self, empty: V) -> VecMat<V> {
+        let Some((min, _)) = self.bounds() else { return VecMat::new(0, 0, empty) };
+        let mut mat = VecMat::new(self.width().to_usize().unwrap(), self.height().to_usize().unwrap(), empty);
+
+        for (pos, val) in self.iter() {
+            mat[(pos - min).to_pos2d().unwrap()] = *val;
+        }
+
+        mat
+    }
+}
+
+impl<I: PrimInt + Signed + Hash> SparseGrid<I> {
+    // Marks a position as occupied, returns whether it was empty before
+    pub fn add(&mut self, pos: Coords<I>) -> bool {
+        self.insert(pos, ()).is_none()
+    }
+}
+
+impl<I: PrimInt + Signed + Hash, V> Default for SparseGrid<I, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: PrimInt + Signed + Hash, V> FromIterator<(Coords<I>, V)> for SparseGrid<I, V> {
+    fn from_iter<It: IntoIterator<Item = (Coords<I>, V)>>(iter: It) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<I: PrimInt + Signed + Hash, V> Extend<(Coords<I>, V)> for SparseGrid<I, V> {
+    fn extend<It: IntoIterator<Item = (Coords<I>, V)>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|(pos, val)| { self.insert(pos, val); });
+    }
+}
+
+impl<I: PrimInt + Signed + Hash> FromIterator<Coords<I>> for SparseGrid<I> {
+    fn from_iter<It: IntoIterator<Item = Coords<I>>>(iter: It) -> Self {
+        iter.into_iter().map(|pos| (pos, ())).collect()
+    }
+}
+
+impl<I: PrimInt + Signed + Hash> Extend<Coords<I>> for SparseGrid<I> {
+    fn extend<It: IntoIterator<Item = Coords<I>>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|pos| { self.add(pos); });
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn decrement<I: Ord>(counter: &mut BTreeMap<I, usize>, key: I) {
+    if let Some(count) = counter.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            counter.remove(&key);
+        }
+    }
+}
+
+fn range_inclusive<I: PrimInt>(start: I, end: I) -> impl Iterator<Item = I> {
+    let mut current = start;
+    std::iter::from_fn(move || {
+        (current <= end).then(|| {
+            let val = current;
+            current = current + I::one();
+            val
+        })
+    })
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Pos = Coords<i32>;
+
+    #[test]
+    fn bounds_follow_insertions_and_removals() {
+        let mut grid: SparseGrid<i32> = SparseGrid::new();
+        assert!(grid.is_empty() && grid.bounds().is_none());
+        assert_eq!(grid.area(), 0);
+
+        grid.extend([Pos::new(-2, 3), Pos::new(4, 1), Pos::new(0, 0)]);
+        assert!(!grid.add(Pos::new(0, 0)));
+        assert_eq!(grid.bounds(), Some((Pos::new(-2, 0), Pos::new(4, 3))));
+        assert_eq!((grid.len(), grid.width(), grid.height()), (3, 7, 4));
+
+        // Removing the only cell in the leftmost column shrinks the box
+        grid.remove(&Pos::new(-2, 3));
+        assert_eq!(grid.bounds(), Some((Pos::new(0, 0), Pos::new(4, 1))));
+        grid.remove(&Pos::new(-2, 3));
+        assert_eq!(grid.len(), 2);
+    }
+
+    #[test]
+    fn values_and_neighbors() {
+        let mut grid: SparseGrid<i32, char> = [(Pos::new(0, 0), 'a'), (Pos::new(1, 0), 'b'), (Pos::new(1, 1), 'c')].into_iter().collect();
+        *grid.get_mut(&Pos::new(1, 1)).unwrap() = 'd';
+
+        let mut near = grid.neighbors4(Pos::new(1, 0)).map(|(_, v)| *v).collect::<Vec<_>>();
+        near.sort();
+        assert_eq!(near, ['a', 'd']);
+        assert_eq!(grid.neighbors8(Pos::new(0, 1)).count(), 3);
+
+        let mut cells = grid.iter().map(|(pos, v)| (pos.x, pos.y, *v)).collect::<Vec<_>>();
+        cells.sort();
+        assert_eq!(cells, [(0, 0, 'a'), (1, 0, 'b'), (1, 1, 'd')]);
+        assert_eq!(grid.render(|v| v.copied().unwrap_or('.')), "ab\n.d");
+    }
+
+    #[test]
+    fn round_trip_through_vecmat() {
+        let grid: SparseGrid<i32, u8> = [(Pos::new(-1, 2), 1), (Pos::new(1, 3), 2)].into_iter().collect();
+        let mat = grid.to_vecmat(0);
+        assert_eq!((mat.width(), mat.height()), (3, 2));
+        assert_eq!((mat[(0, 0)], mat[(2, 1)], mat[(1, 0)]), (1, 2, 0));
+
+        // The top-left corner moves to the origin
+        let back = SparseGrid::<i32, u8>::from_vecmat(&mat, |v| v != 0);
+        assert_eq!(back.render(|v| char::from(b'0' + v.copied().unwrap_or(0))), grid.render(|v| char::from(b'0' + v.copied().unwrap_or(0))));
+        assert_eq!(back.bounds(), Some((Pos::new(0, 0), Pos::new(2, 1))));
+        assert_eq!(SparseGrid::<i32, u8>::new().to_vecmat(0).width(), 0);
+    }
+}
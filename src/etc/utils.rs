@@ -1,7 +1,11 @@
-#[cfg(windows)]
-pub const DOUBLE_NEWLINE: &str = "\r\n\r\n";
-#[cfg(not(windows))]
-pub const DOUBLE_NEWLINE: &str = "\n\n";
+use std::fmt::Debug;
+use std::fs::{read_to_string, File};
+use std::io::{self, BufRead, BufReader};
+use std::iter;
+use std::ops::Deref;
+use std::str::{FromStr, Lines};
+
+use super::vecmat::VecMat;
 
 // General directions to move around a 2D array
 pub const UP: (i32, i32) = (0, -1);
@@ -10,3 +14,169 @@ pub const RIGHT: (i32, i32) = (1, 0);
 pub const LEFT: (i32, i32) = (-1, 0);
 
 pub type Pos2D = (usize, usize);
+
+/** The contents of a puzzle input, normalized so that parsing doesn't depend
+    on how the file was saved: no BOM, LF line endings, blank lines that are
+    truly empty and no trailing whitespace at the end */
+pub struct Input {
+    text: String,
+}
+
+// Reads and normalizes the input file for the given day
+pub fn read_input(day: u8) -> Input {
+    let path = format!("input/day{day:02}.txt");
+    let raw = read_to_string(&path).unwrap_or_else(|e| panic!("Can't read {path}: {e}"));
+    Input::new(&raw)
+}
+
+// Opens the input file for the given day to read it as a stream of bytes,
+// skipping the BOM if it has one, but otherwise as is
+pub fn open_input(day: u8) -> BufReader<File> {
+    let path = format!("input/day{day:02}.txt");
+    let file = File::open(&path).unwrap_or_else(|e| panic!("Can't read {path}: {e}"));
+    let mut reader = BufReader::new(file);
+    skip_bom(&mut reader).unwrap_or_else(|e| panic!("Can't read {path}: {e}"));
+    reader
+}
+
+fn skip_bom<R: BufRead>(reader: &mut R) -> io::Result<()> {
+    if reader.fill_buf()?.starts_with("\u{feff}".as_bytes()) {
+        reader.consume(3);
+    }
+    Ok(())
+}
+
+// Reads the input file for the given day one line at a time, normalizing every
+// line like read_input does, for inputs that are too large to keep in memory
+pub fn stream_input(day: u8) -> impl Iterator<Item = String> {
+    let path = format!("input/day{day:02}.txt");
+    normalized_lines(open_input(day))
+        .map(move |line| line.unwrap_or_else(|e| panic!("Can't read {path}: {e}")))
+}
+
+// Same normalization as Input::new, one line at a time. Blank lines are held
+// back until a line with content comes, so that the trailing ones are dropped.
+fn normalized_lines<R: BufRead>(reader: R) -> impl Iterator<Item = io::Result<String>> {
+    let mut pending_blanks = 0;
+
+    // Lines already come without their \n or \r\n
+    reader.lines().enumerate().flat_map(move |(i, line)| {
+        let line = match line {
+            Ok(line) if i == 0 => line.trim_start_matches('\u{feff}').to_owned(),
+            Ok(line) => line,
+            Err(e) => return vec![Err(e)],
+        };
+
+        if line.trim().is_empty() {
+            pending_blanks += 1;
+            vec![]
+        } else {
+            let blanks = std::mem::take(&mut pending_blanks);
+            iter::repeat_n(String::new(), blanks).chain(iter::once(line)).map(Ok).collect()
+        }
+    })
+}
+
+impl Input {
+    pub fn new(raw: &str) -> Self {
+        let text = raw.trim_start_matches('\u{feff}')
+            .lines()  // Takes care of both \n and \r\n
+            .map(|line| if line.trim().is_empty() { "" } else { line })
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_owned();
+
+        Self { text }
+    }
+
+    pub fn lines(&self) -> Lines<'_> {
+        self.text.lines()
+    }
+
+    // Groups of lines separated by one or more blank lines
+    pub fn paragraphs(&self) -> impl Iterator<Item = &str> {
+        self.text.split("\n\n").map(|p| p.trim_matches('\n')).filter(|p| !p.is_empty())
+    }
+
+    // Parses every line as a number
+    pub fn ints<T>(&self) -> Vec<T>
+    where T: FromStr, T::Err: Debug {
+        self.lines()
+            .map(|line| line.trim().parse().unwrap_or_else(|e| panic!("Not a number: {line:?} ({e:?})")))
+            .collect()
+    }
+
+    // Turns the input into a matrix, converting each character with the provided
+    // function. Lines shorter than the longest one are padded with spaces.
+    pub fn grid<T, F>(&self, to_cell: F) -> VecMat<T>
+    where T: Copy, F: Fn(char) -> T {
+        let width = self.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let height = self.lines().count();
+
+        let data = self.lines()
+            .flat_map(|line| line.chars().chain(std::iter::repeat(' ')).take(width))
+            .map(to_cell)
+            .collect();
+
+        VecMat::from_data(width, height, data)
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLEAN: &str = "12\n3\n\n45\n\n\n6";
+    // Same contents with a BOM, CRLF endings, whitespace in the blank lines and trailing blank lines
+    const MESSY: &str = "\u{feff}12\r\n3\r\n  \r\n45\r\n\t\r\n\r\n6\r\n\r\n \r\n";
+
+    fn cells(input: &Input) -> Vec<(Pos2D, char)> {
+        input.grid(|c| c).indexed_iter().collect()
+    }
+
+    #[test]
+    fn messy_input_reads_like_clean_input() {
+        let (clean, messy) = (Input::new(CLEAN), Input::new(MESSY));
+        assert_eq!(&*messy, CLEAN);
+        assert_eq!(messy.lines().collect::<Vec<_>>(), clean.lines().collect::<Vec<_>>());
+        assert_eq!(messy.paragraphs().collect::<Vec<_>>(), ["12\n3", "45", "6"]);
+        assert_eq!(messy.paragraphs().collect::<Vec<_>>(), clean.paragraphs().collect::<Vec<_>>());
+        assert_eq!(cells(&messy), cells(&clean));
+    }
+
+    #[test]
+    fn ints_skip_the_normalized_blank_lines() {
+        let (clean, messy) = (Input::new("1\n-20\n300"), Input::new("\u{feff}1\r\n-20\r\n300\r\n\r\n"));
+        assert_eq!(messy.ints::<i32>(), [1, -20, 300]);
+        assert_eq!(messy.ints::<i32>(), clean.ints::<i32>());
+    }
+
+    #[test]
+    fn streamed_lines_match_the_whole_input() {
+        let streamed = |text: &str| normalized_lines(text.as_bytes()).map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(streamed(MESSY), Input::new(MESSY).lines().collect::<Vec<_>>());
+        assert_eq!(streamed(MESSY), streamed(CLEAN));
+        assert!(streamed("\r\n\r\n").is_empty());
+    }
+
+    #[test]
+    fn only_a_leading_bom_is_skipped() {
+        let mut bytes = "\u{feff}ab\u{feff}".as_bytes();
+        skip_bom(&mut bytes).unwrap();
+        assert_eq!(bytes, "ab\u{feff}".as_bytes());
+
+        let mut bytes = "ab".as_bytes();
+        skip_bom(&mut bytes).unwrap();
+        assert_eq!(bytes, b"ab");
+    }
+}
@@ -8,6 +8,7 @@ use num_traits::sign::Signed;
 
 use super::utils::Pos2D;
 use super::coords::Coords;
+use super::image::{Image, Pixel};
 
 /** A 2D-like structure backed by a Vec */
 #[derive(Clone, Debug)]
@@ -32,7 +33,7 @@ impl<T: Copy> VecMat<T> {
         Self { width, height, data }
     }
 
-    pub fn indexed_iter(&self) -> VecMaxIndexedIter<T> {
+    pub fn indexed_iter(&self) -> VecMaxIndexedIter<'_, T> {
         VecMaxIndexedIter::new(self)
     }
 
@@ -44,6 +45,12 @@ impl<T: Copy> VecMat<T> {
         self.height
     }
 
+    // Draws every cell as a square of scale x scale pixels, with the given color
+    pub fn to_image<P, F>(&self, scale: usize, to_color: F) -> Image<P>
+    where P: Pixel, F: Fn(T) -> P {
+        Image::from_fn(self.width, self.height, scale, |x, y| to_color(self.data[self.index(x, y)]))
+    }
+
     ////////////////////////////////////////////////////////////////////////////
 
     fn index(&self, x: usize, y: usize) -> usize {
@@ -0,0 +1,181 @@
+use std::io::{self, BufRead, Write};
+use std::mem;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/** A simulation that can be played as an animation in the terminal. Solvers
+    call `new_simulation` before starting one and `show` after every step,
+    which do nothing unless the runner was started with --viz, so the
+    solvers don't need to know about it otherwise. */
+pub trait Visualize {
+    // Draws the current state of the simulation as lines of text
+    fn frame(&self) -> String;
+
+    // A short line shown below the frame, such as the current step
+    fn caption(&self) -> String {
+        String::new()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct VizOptions {
+    pub fps: f64,
+    // Start paused, waiting for Enter before every frame
+    pub step: bool,
+    // Frames are cropped to this size so that they fit in the terminal
+    pub max_width: usize,
+    pub max_height: usize,
+}
+
+struct Player {
+    options: VizOptions,
+    paused: bool,
+    last_frame: Instant,
+    commands: Receiver<String>,
+    // The user skipped the rest of the current simulation
+    skipping: bool,
+    // Time spent drawing frames and waiting between them
+    playing_time: Duration,
+}
+
+// Only one simulation runs at a time, but it has to be reachable from any solver
+static PLAYER: Mutex<Option<Player>> = Mutex::new(None);
+
+// The range of frame rates that can be chosen, either with --fps or while playing
+pub const MIN_FPS: f64 = 0.5;
+pub const MAX_FPS: f64 = 1000.0;
+
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+const CONTROLS: &str = "[Enter] pause/step  [c] continue  [+/-] speed  [q] skip";
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Starts playing the frames of every simulation from now on. The keys are
+// read from stdin in the background, one line at a time.
+pub fn enable(options: VizOptions) {
+    let (sender, commands) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            if sender.send(line.trim().to_owned()).is_err() {
+                break;
+            }
+        }
+    });
+
+    print!("{HIDE_CURSOR}");
+    let player = Player {
+        options, paused: options.step, last_frame: Instant::now(), commands,
+        skipping: false, playing_time: Duration::ZERO,
+    };
+    *PLAYER.lock().unwrap() = Some(player);
+}
+
+// Whether the frames of the current simulation are being played
+pub fn is_enabled() -> bool {
+    PLAYER.lock().unwrap().as_ref().is_some_and(|player| !player.skipping)
+}
+
+// Marks the start of another simulation, which is played even if
+// the user skipped the previous one
+pub fn new_simulation() {
+    let mut guard = PLAYER.lock().unwrap();
+    if let Some(player) = guard.as_mut().filter(|player| player.skipping) {
+        player.skipping = false;
+        print!("{HIDE_CURSOR}");
+    }
+}
+
+// Draws the current state of a simulation, if visualization is enabled, and
+// waits until it's time for the next frame (or for the user, when paused)
+pub fn show<V: Visualize + ?Sized>(sim: &V) {
+    let mut guard = PLAYER.lock().unwrap();
+    let Some(player) = guard.as_mut().filter(|player| !player.skipping) else { return };
+
+    let start = Instant::now();
+    player.draw(sim);
+    if !player.wait() {
+        // The user wants to skip the rest of this simulation
+        player.skipping = true;
+        finish();
+    }
+    player.playing_time += start.elapsed();
+}
+
+// Returns the time spent playing frames since the last call, so
+// that it can be left out of the time taken by the solvers
+pub fn take_playing_time() -> Duration {
+    PLAYER.lock().unwrap().as_mut().map_or(Duration::ZERO, |player| mem::take(&mut player.playing_time))
+}
+
+// Restores the terminal after an animation
+pub fn finish() {
+    print!("{SHOW_CURSOR}");
+    io::stdout().flush().unwrap();
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+impl Player {
+    fn draw<V: Visualize + ?Sized>(&self, sim: &V) {
+        let frame = sim.frame().lines()
+            .take(self.options.max_height)
+            .map(|line| line.chars().take(self.options.max_width).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let status = if self.paused { "paused".to_owned() } else { format!("{:.0} fps", self.options.fps) };
+
+        let mut out = io::stdout().lock();
+        write!(out, "{CLEAR_SCREEN}{frame}\n\n{BOLD}{}{RESET}\n{status} | {CONTROLS}\n", sim.caption()).unwrap();
+        out.flush().unwrap();
+    }
+
+    // Returns false if the user asked to stop the animation
+    fn wait(&mut self) -> bool {
+        if self.paused {
+            // Wait for a command, any change of speed is shown with the next frame
+            match self.commands.recv().as_deref() {
+                Ok("c") => self.paused = false,
+                Ok(command) => return self.handle(command),
+                // Nobody can resume the animation, so just play it
+                Err(_) => self.paused = false,
+            }
+        } else {
+            match self.commands.try_recv() {
+                Ok(command) if command.is_empty() => self.paused = true,
+                Ok(command) => return self.handle(&command),
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => {},
+            }
+
+            let delay = Duration::from_secs_f64(1.0 / self.options.fps);
+            if let Some(remaining) = delay.checked_sub(self.last_frame.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+
+        self.last_frame = Instant::now();
+        true
+    }
+
+    fn handle(&mut self, command: &str) -> bool {
+        match command {
+            "q" => return false,
+            "+" => self.options.fps = (self.options.fps * 2.0).min(MAX_FPS),
+            "-" => self.options.fps = (self.options.fps / 2.0).max(MIN_FPS),
+            _ => {},
+        }
+        true
+    }
+}
+
+impl Default for VizOptions {
+    fn default() -> Self {
+        Self { fps: 30.0, step: false, max_width: 160, max_height: 50 }
+    }
+}
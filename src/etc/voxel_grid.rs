@@ -0,0 +1,225 @@
+// Not every accessor and neighbourhood is used by day 18, they are kept so
+// that VoxelGrid offers the same operations as VecMat in 3D
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::ops::{Index, IndexMut};
+
+use itertools::Itertools;
+
+use super::coords_n::CoordsN;
+use super::vecmat::VecMat;
+
+pub type Voxel = CoordsN<i32, 3>;
+
+/** A 3D box of values backed by a Vec, which can start at any position
+    (including negative ones) instead of only at the origin */
+#[derive(Clone, Debug)]
+pub struct VoxelGrid<T: Copy> {
+    min: Voxel,
+    size: [usize; 3],
+    data: Vec<T>,
+}
+
+impl<T: Copy> VoxelGrid<T> {
+    // Creates a grid that covers the box between both corners, inclusive
+    pub fn new(min: Voxel, max: Voxel, default: T) -> Self {
+        let size = std::array::from_fn(|i| {
+            assert!(max[i] >= min[i], "Max corner {max:?} is below min corner {min:?}");
+            (max[i] - min[i]) as usize + 1
+        });
+        let data = vec![default; size.iter().product()];
+        Self { min, size, data }
+    }
+
+    pub fn min(&self) -> Voxel {
+        self.min
+    }
+
+    pub fn max(&self) -> Voxel {
+        Voxel::new(std::array::from_fn(|i| self.min[i] + self.size[i] as i32 - 1))
+    }
+
+    pub fn size(&self) -> [usize; 3] {
+        self.size
+    }
+
+    pub fn contains(&self, pos: &Voxel) -> bool {
+        pos.is_within(&self.min, &self.max())
+    }
+
+    pub fn get(&self, pos: &Voxel) -> Option<&T> {
+        self.contains(pos).then(|| &self.data[self.index(pos)])
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Voxel, T)> + '_ {
+        self.data.iter().enumerate().map(|(i, val)| (self.voxel(i), *val))
+    }
+
+    // In-bounds voxels that share a face with the given one
+    pub fn neighbors6(&self, pos: &Voxel) -> impl Iterator<Item = Voxel> + '_ {
+        pos.neighbors().filter(|n| self.contains(n))
+    }
+
+    // In-bounds voxels that share a face, edge or corner with the given one
+    pub fn neighbors26(&self, pos: &Voxel) -> impl Iterator<Item = Voxel> + '_ {
+        let center = *pos;
+        (0..3).map(|_| -1..=1).multi_cartesian_product()
+            .filter(|delta| delta.iter().any(|&d| d != 0))
+            .map(move |delta| center + Voxel::new([delta[0], delta[1], delta[2]]))
+            .filter(|n| self.contains(n))
+    }
+
+    // Extracts the 2D plane where the given axis has a fixed value. The
+    // remaining two axes, in their original order, become the matrix's X and Y.
+    pub fn slice(&self, axis: usize, value: i32) -> VecMat<T> {
+        assert!(axis < 3, "Axis out of bounds: {axis}");
+        let (ax, ay) = match axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        };
+
+        let data = (0..self.size[ay]).cartesian_product(0..self.size[ax]).map(|(y, x)| {
+            let mut pos = self.min;
+            pos[axis] = value;
+            pos[ax] += x as i32;
+            pos[ay] += y as i32;
+            self[pos]
+        }).collect();
+
+        VecMat::from_data(self.size[ax], self.size[ay], data)
+    }
+
+    // Returns every voxel that can be reached from the starting one by moving
+    // across faces, only through voxels whose values satisfy the condition.
+    // Nothing is filled if the starting voxel doesn't satisfy it either.
+    pub fn flood_fill<F>(&self, start: Voxel, can_enter: F) -> Vec<Voxel>
+    where F: Fn(T) -> bool {
+        if !can_enter(self[start]) {
+            return vec![];
+        }
+
+        let mut visited = vec![false; self.data.len()];
+        let mut to_visit = VecDeque::from([start]);
+        let mut filled = vec![];
+
+        visited[self.index(&start)] = true;
+
+        while let Some(pos) = to_visit.pop_front() {
+            filled.push(pos);
+
+            for next in self.neighbors6(&pos) {
+                let i = self.index(&next);
+                if !visited[i] && can_enter(self.data[i]) {
+                    visited[i] = true;
+                    to_visit.push_back(next);
+                }
+            }
+        }
+
+        filled
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn index(&self, pos: &Voxel) -> usize {
+        assert!(self.contains(pos), "Voxel out of bounds: {pos:?}");
+        let [x, y, z] = std::array::from_fn(|i| (pos[i] - self.min[i]) as usize);
+        (z * self.size[1] + y) * self.size[0] + x
+    }
+
+    fn voxel(&self, index: usize) -> Voxel {
+        let (x, y, z) = (index % self.size[0], index / self.size[0] % self.size[1], index / (self.size[0] * self.size[1]));
+        self.min + Voxel::new([x as i32, y as i32, z as i32])
+    }
+}
+
+impl<T: Copy> Index<Voxel> for VoxelGrid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Voxel) -> &Self::Output {
+        &self.data[self.index(&pos)]
+    }
+}
+
+impl<T: Copy> IndexMut<Voxel> for VoxelGrid<T> {
+    fn index_mut(&mut self, pos: Voxel) -> &mut Self::Output {
+        let i = self.index(&pos);
+        &mut self.data[i]
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every voxel holds its own position as a number, such as 123 for (1, 2, 3)
+    fn numbered_grid() -> VoxelGrid<i32> {
+        let mut grid = VoxelGrid::new(Voxel::new([0, 0, 0]), Voxel::new([1, 2, 3]), 0);
+        for (pos, _) in grid.clone().indexed_iter() {
+            grid[pos] = pos[0] * 100 + pos[1] * 10 + pos[2];
+        }
+        grid
+    }
+
+    fn rows(mat: &VecMat<i32>) -> Vec<Vec<i32>> {
+        (0..mat.height()).map(|y| (0..mat.width()).map(|x| mat[(x, y)]).collect()).collect()
+    }
+
+    #[test]
+    fn can_start_at_negative_positions() {
+        let mut grid = VoxelGrid::new(Voxel::new([-1, 0, -2]), Voxel::new([1, 1, 0]), 0);
+        assert_eq!((grid.size(), grid.max()), ([3, 2, 3], Voxel::new([1, 1, 0])));
+
+        grid[Voxel::new([-1, 1, -2])] = 5;
+        assert_eq!(grid.get(&Voxel::new([-1, 1, -2])), Some(&5));
+        assert_eq!(grid.get(&Voxel::new([-2, 1, -2])), None);
+
+        // Every voxel is visited once, and indexed by its own position
+        let cells = grid.indexed_iter().collect::<Vec<_>>();
+        assert_eq!(cells.len(), 18);
+        assert!(cells.iter().all(|&(pos, val)| grid[pos] == val));
+        assert_eq!(cells.iter().filter(|(_, val)| *val == 5).count(), 1);
+    }
+
+    #[test]
+    fn slices_keep_the_remaining_axes_in_order() {
+        let grid = numbered_grid();
+
+        // X fixed: Y goes right and Z goes down
+        assert_eq!(rows(&grid.slice(0, 1)), [[100, 110, 120], [101, 111, 121], [102, 112, 122], [103, 113, 123]]);
+        // Y fixed: X goes right and Z goes down
+        assert_eq!(rows(&grid.slice(1, 2)), [[20, 120], [21, 121], [22, 122], [23, 123]]);
+        // Z fixed: X goes right and Y goes down
+        assert_eq!(rows(&grid.slice(2, 3)), [[3, 103], [13, 113], [23, 123]]);
+    }
+
+    #[test]
+    fn neighbors_stay_in_bounds() {
+        let grid = VoxelGrid::new(Voxel::splat(0), Voxel::splat(2), ());
+        assert_eq!(grid.neighbors26(&Voxel::splat(1)).count(), 26);
+        assert_eq!(grid.neighbors26(&Voxel::splat(0)).count(), 7);
+        assert_eq!(grid.neighbors6(&Voxel::splat(0)).count(), 3);
+    }
+
+    #[test]
+    fn flood_fill_goes_around_walls() {
+        // A wall at x = 1 with a single hole at the top corner
+        let mut grid = VoxelGrid::new(Voxel::splat(0), Voxel::splat(2), false);
+        for y in 0..3 {
+            for z in 0..3 {
+                grid[Voxel::new([1, y, z])] = (y, z) != (2, 2);
+            }
+        }
+
+        let filled = grid.flood_fill(Voxel::splat(0), |wall| !wall);
+        assert_eq!(filled.len(), 19);
+        assert!(filled.contains(&Voxel::new([2, 0, 0])));
+
+        // Starting inside the wall fills nothing
+        assert!(grid.flood_fill(Voxel::new([1, 0, 0]), |wall| !wall).is_empty());
+    }
+}
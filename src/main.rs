@@ -1,27 +1,38 @@
 mod days;
 mod etc;
 
-use etc::solution::Solution;
+use etc::Solution;
+use etc::image;
+use etc::visualize::{self, VizOptions, MIN_FPS, MAX_FPS};
 use days::{day01, day02, day03, day04, day05,
            day06, day07, day08, day09, day10,
            day11, day12, day13, day14, day15,
            day16, day17, day18, day19, day20,
            day21, day22, day23, day24, day25};
 use std::env;
+use std::path::PathBuf;
 use std::time::Instant;
 
 pub type SolutionPair = (Solution, Solution);
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
+    let (flags, days): (Vec<String>, Vec<String>) = env::args().skip(1).partition(|arg| arg.starts_with("--"));
+    if days.is_empty() {
         panic!("Please provide the day(s) to run as a command-line argument.");
     }
 
-    let days: Vec<u8> = args[1..].iter()
+    let days: Vec<u8> = days.iter()
         .map(|x| x.parse().unwrap_or_else(|v| panic!("Not a valid day: {v}")))
         .collect();
 
+    let (viz_options, image_dir) = parse_flags(&flags);
+    if let Some(options) = viz_options {
+        visualize::enable(options);
+    }
+    if let Some(dir) = image_dir {
+        image::enable_output(dir);
+    }
+
     let mut runtime = 0.0;
 
     for day in days {
@@ -29,12 +40,22 @@ fn main() {
 
         let time = Instant::now();
         let (p1, p2) = func();
-        let elapsed_ms = time.elapsed().as_nanos() as f64 / 1_000_000.0;
+        // Drawing and waiting between frames doesn't count as solving
+        let elapsed = time.elapsed().saturating_sub(visualize::take_playing_time());
+        let elapsed_ms = elapsed.as_nanos() as f64 / 1_000_000.0;
+
+        if viz_options.is_some() {
+            visualize::finish();
+        }
         
         println!("\n=== Day {day:02} ===");
         println!("  · Part 1: {p1}");
         println!("  · Part 2: {p2}");
-        println!("  · Elapsed: {elapsed_ms:.4} ms");
+        if viz_options.is_some() {
+            println!("  · Elapsed: {elapsed_ms:.4} ms (not counting the animation)");
+        } else {
+            println!("  · Elapsed: {elapsed_ms:.4} ms");
+        }
 
         runtime += elapsed_ms;
     }
@@ -42,6 +63,28 @@ fn main() {
     println!("Total runtime: {runtime:.4} ms");
 }
 
+// Visualization flags: --viz plays the simulations, --fps=N sets their speed
+// and --step starts them paused. Also, --images=DIR saves pictures there.
+fn parse_flags(flags: &[String]) -> (Option<VizOptions>, Option<PathBuf>) {
+    let mut options = VizOptions::default();
+    let mut enabled = false;
+    let mut image_dir = None;
+
+    for flag in flags {
+        match flag.split_once('=') {
+            None if flag == "--viz" => enabled = true,
+            None if flag == "--step" => options.step = true,
+            Some(("--fps", fps)) => options.fps = fps.parse().ok()
+                .filter(|fps| (MIN_FPS..=MAX_FPS).contains(fps))
+                .unwrap_or_else(|| panic!("Not a valid frame rate: {fps} (it must be between {MIN_FPS} and {MAX_FPS})")),
+            Some(("--images", dir)) => image_dir = Some(PathBuf::from(dir)),
+            _ => panic!("Unknown flag: {flag}"),
+        }
+    }
+
+    (enabled.then_some(options), image_dir)
+}
+
 fn get_day_solver(day: u8) -> fn() -> SolutionPair {
     match day {
          1 => day01::solve,